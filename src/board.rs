@@ -1,56 +1,145 @@
-use crate::piece::{get_piece, Piece};
-use crossterm::style::Color;
+use crate::piece::{get_piece, kind, Piece, PieceKind};
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::fmt;
+use std::time::Instant;
 
-const WIDTH: usize = 10;
-const HEIGHT: usize = 20;
 const BORDER_WIDTH: usize = 1;
 const PIECE_DIM: usize = 4;
 
-/// The Board contains all of the pieces that are placed into the pile of tetrominos. The Board
-/// keeps track of the locations as well as the colors. If I feel like cleaning this up, I can just
-/// use the color_board to keep track of all of the blocks
+/// The number of hidden rows above the visible part of the board. Pieces spawn at the bottom of
+/// this zone and anything on the stack can be pushed up into it without ending the game.
+const BUFFER_HEIGHT: usize = 20;
+
+/// The ways that a game can end, named the same way as the guideline
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, Copy)]
+pub enum GameOverReason {
+    /// The next piece overlaps the stack where it spawns
+    BlockOut,
+    /// A piece was fixed to the board completely above the visible rows
+    LockOut,
+    /// Garbage pushed part of the stack above the top of the buffer zone
+    TopOut,
+}
+
+impl fmt::Display for GameOverReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameOverReason::BlockOut => {
+                write!(f, "Block out, there was no room for the next piece")
+            }
+            GameOverReason::LockOut => write!(f, "Lock out, a piece locked above the board"),
+            GameOverReason::TopOut => write!(f, "Top out, the stack was pushed off the board"),
+        }
+    }
+}
+
+/// What is in a single cell of the board
+#[derive(Clone, Copy, PartialEq)]
+pub enum Cell {
+    Empty,
+    /// A block of a piece that was fixed to the board
+    Piece(PieceKind),
+    /// A block of a garbage line that was pushed up from the bottom
+    Garbage,
+    /// A block of the stack once the game is over, which doesn't belong to anything anymore
+    Locked,
+}
+
+/// A single square of the board, which remembers when its block was locked in place so it can be
+/// hidden after a while
+#[derive(Clone, Copy)]
+struct Square {
+    cell: Cell,
+    locked_at: Option<Instant>,
+}
+
+const EMPTY: Square = Square {
+    cell: Cell::Empty,
+    locked_at: None,
+};
+
+/// The Board contains all of the pieces that are placed into the pile of tetrominos. Every cell
+/// keeps track of what kind of block is in it, which is also what decides its color.
+///
+/// Pieces are placed in rows and columns of minos, where row 1 is the top visible row. The hidden
+/// buffer zone is made up of row 0 and the rows above it. In big mode every mino covers a square
+/// of scale by scale cells, so the board is stored as a matrix of cells that is scale times wider
+/// and taller than the number of minos that fit on it. Otherwise a mino is a single cell.
 pub struct Board {
     pub width: usize,
     pub height: usize,
-    board: [[u8; WIDTH]; HEIGHT],
-    pub color_board: [[Color; WIDTH]; HEIGHT],
+    pub scale: usize,
+    board: Vec<Vec<Square>>,
 }
 
 impl Board {
-    /// Generated a new board with all of the default values. The board starts of all black.
-    pub fn new() -> Self {
+    /// Generated a new board with the given visible width and height in minos, plus the hidden
+    /// buffer zone above it. The board starts of all empty.
+    pub fn new(width: usize, height: usize, scale: usize) -> Self {
         Self {
-            width: WIDTH,
-            height: HEIGHT,
-            board: [[0u8; WIDTH]; HEIGHT],
-            color_board: [[Color::Black; WIDTH]; HEIGHT],
+            width,
+            height,
+            scale,
+            board: vec![vec![EMPTY; width * scale]; (BUFFER_HEIGHT + height) * scale],
+        }
+    }
+
+    /// Returns the row and column of the cell at the top left of the mino in the given row and
+    /// column. The cells are numbered the same way as the minos, where row 1 is the top visible
+    /// row of cells.
+    fn scaled(&self, row: i16, column: i16) -> (i16, i16) {
+        let border = BORDER_WIDTH as i16;
+        let scale = self.scale as i16;
+        (
+            (row - border) * scale + border,
+            (column - border) * scale + border,
+        )
+    }
+
+    /// Returns the indices into the board for the given row and column of cells, or None if they
+    /// are outside of the board. The row and column can be negative since wall kicks are able to
+    /// push a piece above or left of the board, so the bounds are checked before the values are
+    /// converted into indices.
+    fn index(&self, row: i16, column: i16) -> Option<(usize, usize)> {
+        let board_row = row - BORDER_WIDTH as i16 + (BUFFER_HEIGHT * self.scale) as i16;
+        let board_column = column - BORDER_WIDTH as i16;
+        if board_row < 0
+            || board_row >= self.board.len() as i16
+            || board_column < 0
+            || board_column >= (self.width * self.scale) as i16
+        {
+            None
+        } else {
+            Some((board_row as usize, board_column as usize))
+        }
+    }
+
+    /// Returns the indices into the board of every cell that the mino in the given row and column
+    /// covers, skipping the cells that are outside of the board
+    fn mino_indices(&self, row: i16, column: i16) -> Vec<(usize, usize)> {
+        let (row, column) = self.scaled(row, column);
+        let scale = self.scale as i16;
+        let mut indices = Vec::with_capacity(self.scale * self.scale);
+        for r in row..row + scale {
+            for c in column..column + scale {
+                if let Some(index) = self.index(r, c) {
+                    indices.push(index);
+                }
+            }
         }
+        indices
     }
 
     /// Returns whether or not a collision is detected between the given piece and any of the
-    /// elements on the board or if the piece will exceed the boundaries of the board. wrapping_add
-    /// is used to prevent the case of i16 conversions of -1 turning into 65535. Ideally, I
-    /// would fix this.
+    /// elements on the board or if the piece will exceed the boundaries of the board.
     pub fn detect_collision(&self, piece: Piece, row: i16, column: i16) -> bool {
         let matched_piece = get_piece(piece);
-        for r in 0..matched_piece.len() {
-            for c in 0..matched_piece[0].len() {
-                if matched_piece[r][c] == 1 {
-                    if (row + r as i16 - BORDER_WIDTH as i16) < 0
-                        || (row + r as i16 - BORDER_WIDTH as i16) >= (self.height as i16)
-                    {
-                        return true;
-                    } else if (column + c as i16 - BORDER_WIDTH as i16) < 0
-                        || (column.wrapping_add(c as i16) - BORDER_WIDTH as i16)
-                            >= (self.width as i16)
-                    {
-                        return true;
-                    } else if self.board[row as usize + r as usize - BORDER_WIDTH]
-                        [column.wrapping_add(c as i16) as usize - BORDER_WIDTH]
-                        == 1
-                    {
-                        return true;
-                    }
+        for (r, piece_row) in matched_piece.iter().enumerate() {
+            for (c, &cell) in piece_row.iter().enumerate() {
+                if cell == 1 && self.is_occupied(row + r as i16, column + c as i16) {
+                    return true;
                 }
             }
         }
@@ -58,67 +147,244 @@ impl Board {
         false
     }
 
-    /// Returns whether or not the game has ended
-    pub fn detect_endgame(&self, piece: Piece, row: i16) -> bool {
+    /// Returns whether or not the mino at the given row and column is filled, using the same
+    /// coordinates as the pieces. Anything outside of the board counts as filled.
+    pub fn is_occupied(&self, row: i16, column: i16) -> bool {
+        let indices = self.mino_indices(row, column);
+        indices.len() < self.scale * self.scale
+            || indices
+                .iter()
+                .any(|&(r, c)| self.board[r][c].cell != Cell::Empty)
+    }
+
+    /// Returns the cell in the given visible row and column of cells, both starting at 0
+    pub fn cell(&self, row: usize, column: usize) -> Cell {
+        self.board[BUFFER_HEIGHT * self.scale + row][column].cell
+    }
+
+    /// Returns when the block in the given visible row and column of cells was locked in place,
+    /// or None when the cell is empty
+    pub fn locked_at(&self, row: usize, column: usize) -> Option<Instant> {
+        self.board[BUFFER_HEIGHT * self.scale + row][column].locked_at
+    }
+
+    /// Returns the number of rows of minos that have garbage in them
+    pub fn garbage_rows(&self) -> usize {
+        let rows = self
+            .board
+            .iter()
+            .filter(|row| row.iter().any(|square| square.cell == Cell::Garbage))
+            .count();
+        rows / self.scale
+    }
+
+    /// Returns the column of the hole in the bottom row, if the bottom row is garbage. This is the
+    /// last hole that was pushed in, so more garbage can carry on from it.
+    pub fn bottom_hole(&self) -> Option<usize> {
+        let bottom = self.board.last()?;
+        if bottom.iter().any(|square| square.cell == Cell::Garbage) {
+            let hole = bottom
+                .iter()
+                .position(|square| square.cell == Cell::Empty)?;
+            Some(hole / self.scale)
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether or not there is nothing left on the board
+    pub fn is_empty(&self) -> bool {
+        self.board
+            .iter()
+            .all(|row| row.iter().all(|square| square.cell == Cell::Empty))
+    }
+
+    /// Returns whether or not fixing the given piece to the board is a lock out, which is when
+    /// every block of the piece is above the visible rows
+    pub fn detect_lock_out(&self, piece: Piece, row: i16) -> bool {
         let matched_piece = get_piece(piece);
-        for r in 0..matched_piece.len() {
-            for c in 0..matched_piece[0].len() {
-                if matched_piece[r][c] == 1 {
-                    if row + r as i16 <= BORDER_WIDTH as i16 {
-                        return true;
-                    }
-                }
+        for (r, piece_row) in matched_piece.iter().enumerate() {
+            if piece_row.contains(&1) && row + r as i16 >= BORDER_WIDTH as i16 {
+                return false;
             }
         }
-        false
+        true
     }
 
-    /// Saves the given piece to the board and color_board
-    pub fn save(&mut self, piece: Piece, row: i16, column: i16, color: Color) {
+    /// Saves the given piece to the board
+    pub fn save(&mut self, piece: Piece, row: i16, column: i16) {
         let matched_piece = get_piece(piece);
-        for r in 0..matched_piece.len() {
-            for c in 0..matched_piece[0].len() {
-                if matched_piece[r][c] == 1 {
-                    self.board[row as usize + r as usize - BORDER_WIDTH]
-                        [column.wrapping_add(c as i16) as usize - BORDER_WIDTH] = 1;
-                    self.color_board[row as usize + r as usize - BORDER_WIDTH]
-                        [column.wrapping_add(c as i16) as usize - BORDER_WIDTH] = color;
+        let square = Square {
+            cell: Cell::Piece(kind(piece)),
+            locked_at: Some(Instant::now()),
+        };
+        for (r, piece_row) in matched_piece.iter().enumerate() {
+            for (c, &cell) in piece_row.iter().enumerate() {
+                if cell == 1 {
+                    for (r, c) in self.mino_indices(row + r as i16, column + c as i16) {
+                        self.board[r][c] = square;
+                    }
                 }
             }
         }
     }
 
-    /// Returns the total number of lines that have been completed.
+    /// Returns the total number of lines of minos that have been completed.
     ///
-    /// Deletes the completed lines and shifts everything down
+    /// Deletes the completed rows of cells and shifts everything down. Only the rows down to the
+    /// bottom of the piece that was placed at the given row need to be checked.
     pub fn handle_completed_lines(&mut self, row: i16) -> i32 {
-        let row = std::cmp::min(row - BORDER_WIDTH as i16 + PIECE_DIM as i16, HEIGHT as i16);
+        let (bottom, column) = self.scaled(row + PIECE_DIM as i16, BORDER_WIDTH as i16);
+        let bottom = match self.index(bottom - 1, column) {
+            Some((bottom, _)) => bottom + 1,
+            None => self.board.len(),
+        };
         let mut lines = 0;
 
-        let mut write_row = row - 1;
-        for r in (0..row).rev() {
-            if self.board[r as usize].iter().sum::<u8>() != 10 {
-                if write_row != r {
-                    for c in 0..self.board[0].len() {
-                        self.board[write_row as usize][c] = self.board[r as usize][c];
-                        self.color_board[write_row as usize][c] = self.color_board[r as usize][c];
-                    }
-                }
-                if write_row != 0 {
-                    write_row -= 1;
-                }
-            } else {
+        for r in (0..bottom).rev() {
+            if self.board[r]
+                .iter()
+                .all(|square| square.cell != Cell::Empty)
+            {
+                self.board.remove(r);
                 lines += 1;
             }
         }
 
-        for r in 0..write_row {
-            for c in 0..self.board[r as usize].len() {
-                self.board[r as usize][c] = 0;
-                self.color_board[r as usize][c] = Color::Black;
+        for _ in 0..lines {
+            self.board.insert(0, vec![EMPTY; self.width * self.scale]);
+        }
+
+        (lines / self.scale) as i32
+    }
+
+    /// Removes everything from the board
+    pub fn clear(&mut self) {
+        for row in self.board.iter_mut() {
+            for square in row.iter_mut() {
+                *square = EMPTY;
+            }
+        }
+    }
+
+    /// Turns every block on the board into a locked block, which is done when the game is over
+    pub fn lock(&mut self) {
+        for row in self.board.iter_mut() {
+            for square in row.iter_mut().filter(|square| square.cell != Cell::Empty) {
+                square.cell = Cell::Locked;
             }
         }
+    }
+
+    /// Pushes the given number of garbage rows in from the bottom, moving everything else up.
+    /// Returns whether or not this tops out the player, which is when any of the stack gets
+    /// pushed above the buffer zone.
+    ///
+    /// Every garbage row is full except for a single hole, which starts in the given column. The
+    /// messiness is the chance, from 0 to 1, that the hole moves to another column for the next
+    /// row. Clean garbage has a messiness of 0, so all of the holes line up, and cheese has a
+    /// messiness of 1, so no two rows in a row have their holes in the same column.
+    pub fn add_garbage(
+        &mut self,
+        lines: usize,
+        hole: usize,
+        messiness: f64,
+        rng: &mut StdRng,
+    ) -> bool {
+        let cells = std::cmp::min(lines * self.scale, self.board.len());
+        let topped_out = self.board[..cells]
+            .iter()
+            .any(|row| row.iter().any(|square| square.cell != Cell::Empty));
 
-        return lines;
+        let garbage = Square {
+            cell: Cell::Garbage,
+            locked_at: Some(Instant::now()),
+        };
+        let mut hole = std::cmp::min(hole, self.width - 1);
+        for _ in 0..cells / self.scale {
+            let mut row = vec![garbage; self.width * self.scale];
+            for square in row.iter_mut().skip(hole * self.scale).take(self.scale) {
+                *square = EMPTY;
+            }
+            for _ in 0..self.scale {
+                self.board.remove(0);
+                self.board.push(row.clone());
+            }
+
+            if rng.gen_bool(messiness.clamp(0.0, 1.0)) {
+                hole = (hole + rng.gen_range(1..self.width)) % self.width;
+            }
+        }
+
+        topped_out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    /// Returns the column of the hole in every visible row of cells, from the top
+    fn holes(board: &Board) -> Vec<Option<usize>> {
+        (0..board.height * board.scale)
+            .map(|r| (0..board.width * board.scale).position(|c| board.cell(r, c) == Cell::Empty))
+            .collect()
+    }
+
+    #[test]
+    fn clean_garbage_lines_up_the_holes() {
+        let mut board = Board::new(10, 20, 1);
+        let mut rng = StdRng::seed_from_u64(0);
+        board.add_garbage(8, 3, 0.0, &mut rng);
+
+        let holes = holes(&board);
+        assert!(holes[..12].iter().all(|hole| *hole == Some(0)));
+        assert!(holes[12..].iter().all(|hole| *hole == Some(3)));
+        assert_eq!(board.garbage_rows(), 8);
+        assert_eq!(board.bottom_hole(), Some(3));
+    }
+
+    #[test]
+    fn messy_garbage_moves_the_hole_every_line() {
+        let mut board = Board::new(10, 20, 1);
+        let mut rng = StdRng::seed_from_u64(0);
+        board.add_garbage(8, 3, 1.0, &mut rng);
+
+        let holes = holes(&board);
+        for pair in holes[12..].windows(2) {
+            assert_ne!(pair[0], pair[1]);
+        }
+        // The first line pushed in is the highest one and keeps the hole it was given
+        assert_eq!(holes[12], Some(3));
+    }
+
+    #[test]
+    fn garbage_tops_out_when_the_top_row_is_pushed_off() {
+        let mut board = Board::new(10, 20, 1);
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(!board.add_garbage(BUFFER_HEIGHT + 20, 0, 0.0, &mut rng));
+        assert!(board.add_garbage(1, 0, 0.0, &mut rng));
+    }
+
+    #[test]
+    fn big_mode_garbage_covers_two_rows_and_columns() {
+        let mut board = Board::new(5, 10, 2);
+        let mut rng = StdRng::seed_from_u64(0);
+        board.add_garbage(2, 1, 0.0, &mut rng);
+
+        assert_eq!(board.garbage_rows(), 2);
+        assert_eq!(board.bottom_hole(), Some(1));
+        for r in 16..20 {
+            for c in 0..10 {
+                let expected = if c == 2 || c == 3 {
+                    Cell::Empty
+                } else {
+                    Cell::Garbage
+                };
+                assert!(board.cell(r, c) == expected);
+            }
+        }
+        assert!(board.cell(15, 0) == Cell::Empty);
     }
 }
@@ -1,5 +1,9 @@
+use crate::board::Cell;
+use crate::piece::PieceKind;
 use crossterm::style::Color;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::time::{Duration, Instant};
 
 /// Painting to the screen can be either Permanent or Temporary
 ///
@@ -14,26 +18,132 @@ pub enum PaintType {
     Temporary,
 }
 
-/// Returns a random color
+/// How the pieces get their colors
+#[derive(Clone, Copy)]
+pub enum ColorMode {
+    /// Every piece type always has the same guideline color
+    Guideline,
+    /// Every piece type gets a different random color at the start of the game
+    Random,
+}
+
+/// How the blocks on the board are shown once they have been locked in place
+#[derive(Clone, Copy)]
+pub enum BlockMode {
+    /// Blocks are always shown
+    Visible,
+    /// Blocks disappear once they have been locked in place for the given time
+    Fading(Duration),
+    /// Blocks disappear as soon as they are locked in place
+    Invisible,
+}
+
+impl BlockMode {
+    /// Returns whether or not a block that was locked in place at the given time is hidden.
+    /// Empty cells don't have a time and are never hidden.
+    pub fn is_hidden(&self, locked_at: Option<Instant>) -> bool {
+        match (self, locked_at) {
+            (_, None) | (BlockMode::Visible, _) => false,
+            (BlockMode::Fading(time), Some(locked_at)) => locked_at.elapsed() >= *time,
+            (BlockMode::Invisible, Some(_)) => true,
+        }
+    }
+}
+
+/// The colors that every kind of block on the board is painted with for the rest of the game
+pub struct Palette {
+    pieces: [Color; 7],
+}
+
+impl Palette {
+    /// Returns the palette for the color mode. In the random color mode, the colors are picked
+    /// once here, so every piece of a kind looks the same for the whole game. No two kinds get the
+    /// same color, so the pieces can still be told apart at a glance.
+    pub fn new(mode: ColorMode, rng: &mut StdRng) -> Self {
+        let mut pieces = [Color::Black; 7];
+        match mode {
+            ColorMode::Guideline => {
+                for (i, &kind) in KINDS.iter().enumerate() {
+                    pieces[i] = piece_color(kind);
+                }
+            }
+            ColorMode::Random => {
+                let mut colors = RANDOM_COLORS;
+                colors.shuffle(rng);
+                pieces.copy_from_slice(&colors[..KINDS.len()]);
+            }
+        }
+        Self { pieces }
+    }
+
+    /// Returns the color of the pieces of the given kind
+    pub fn color(&self, kind: PieceKind) -> Color {
+        self.pieces[kind as usize]
+    }
+
+    /// Returns the color that the cell on the board is painted with
+    pub fn cell_color(&self, cell: Cell) -> Color {
+        match cell {
+            Cell::Empty => Color::Black,
+            Cell::Piece(kind) => self.color(kind),
+            Cell::Garbage => GARBAGE_COLOR,
+            Cell::Locked => LOCKED_COLOR,
+        }
+    }
+}
+
+/// All of the piece kinds in the order that the palette stores them in
+static KINDS: [PieceKind; 7] = [
+    PieceKind::I,
+    PieceKind::J,
+    PieceKind::L,
+    PieceKind::O,
+    PieceKind::S,
+    PieceKind::T,
+    PieceKind::Z,
+];
+
+/// Returns the guideline color of the piece
+///
+/// Orange isn't one of the 16 colors that terminals share, so the L piece uses dark yellow, which
+/// most terminals show as an orange or brown.
+pub fn piece_color(kind: PieceKind) -> Color {
+    match kind {
+        PieceKind::I => Color::Cyan,
+        PieceKind::J => Color::Blue,
+        PieceKind::L => Color::DarkYellow,
+        PieceKind::O => Color::Yellow,
+        PieceKind::S => Color::Green,
+        PieceKind::T => Color::Magenta,
+        PieceKind::Z => Color::Red,
+    }
+}
+
+/// Garbage is grey like the border, since it doesn't belong to any of the pieces
+const GARBAGE_COLOR: Color = Color::Grey;
+
+/// The stack turns white when it gets locked at the end of the game
+const LOCKED_COLOR: Color = Color::White;
+
+/// The ghost piece is drawn dim, so it doesn't get confused with the pieces on the board
+pub const GHOST_COLOR: Color = Color::DarkGrey;
+
+/// The colors that the random color mode picks from
 ///
 /// There are a total of 16 colors, which are compatible amongst terminals, but I have only
 /// selected the colors, which are colorful. e.g. not white, or dark white, or grey since the
 /// border is grey
-pub fn random_color() -> Color {
-    let mut rng = rand::thread_rng();
-    match rng.gen_range(0..=11) {
-        0 => Color::Red,
-        1 => Color::Green,
-        2 => Color::Yellow,
-        3 => Color::Blue,
-        4 => Color::Magenta,
-        5 => Color::Cyan,
-        6 => Color::DarkRed,
-        7 => Color::DarkGreen,
-        8 => Color::DarkYellow,
-        9 => Color::DarkBlue,
-        10 => Color::DarkMagenta,
-        11 => Color::DarkCyan,
-        _ => panic!("RNG went out of range for random color."),
-    }
-}
+static RANDOM_COLORS: [Color; 12] = [
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::DarkRed,
+    Color::DarkGreen,
+    Color::DarkYellow,
+    Color::DarkBlue,
+    Color::DarkMagenta,
+    Color::DarkCyan,
+];
@@ -1,13 +1,17 @@
 use crossterm::event::KeyCode;
 
 /// The possible commands that will control the tetrominos game
+#[derive(Clone, Copy, PartialEq)]
 pub enum Command {
     Empty,
     Left,
     Right,
     Up,
+    RotateCcw,
+    Rotate180,
     Down,
     Space,
+    Hold,
     Escape,
 }
 
@@ -18,6 +22,7 @@ pub fn match_key(code: KeyCode) -> Command {
         KeyCode::Right => Command::Right,
         KeyCode::Down => Command::Down,
         KeyCode::Up => Command::Up,
+        KeyCode::Tab => Command::Hold,
         KeyCode::Esc => Command::Escape,
         KeyCode::Char(c) => match c {
             'a' => Command::Left,
@@ -32,6 +37,14 @@ pub fn match_key(code: KeyCode) -> Command {
             'j' => Command::Down,
             'l' => Command::Right,
             'k' => Command::Up,
+            'x' => Command::Up,
+            'z' => Command::RotateCcw,
+            'q' => Command::Rotate180,
+            'X' => Command::Up,
+            'Z' => Command::RotateCcw,
+            'Q' => Command::Rotate180,
+            'v' => Command::Hold,
+            'V' => Command::Hold,
             ' ' => Command::Space,
             'c' => Command::Escape,
             _ => Command::Empty,
@@ -0,0 +1,222 @@
+use crate::color::{BlockMode, ColorMode};
+use crate::level::{parse_timings, Goal, Timing, MASTER_TIMINGS};
+use crate::mode::Mode;
+use crate::piece::{randomizer, Randomizer};
+use std::time::Duration;
+
+const USAGE: &str = "Usage: rusty-tetrominos [OPTIONS]
+
+Options:
+    --randomizer <NAME>    bag, 14bag, nes, tgm, tgm2 or random [default: bag]
+    --seed <NUMBER>        Replays the game with the given seed [default: random]
+    --das <MS>             Delayed Auto Shift, how long to hold a key before it repeats, at least
+                           the terminal's key repeat delay, master takes it from the timings
+                           instead [default: 167]
+    --arr <MS>             Auto Repeat Rate, how often a held key repeats [default: 33]
+    --sdf <NUMBER>         Soft Drop Factor, how many times faster than gravity to soft drop [default: 6]
+    --level <NUMBER>       The level to start at [default: 1]
+    --goal <GOAL>          fixed for 10 lines a level, or variable for 5 times the level [default: fixed]
+    --width <NUMBER>       The width of the board, at least 4 [default: 10]
+    --height <NUMBER>      The height of the board, at least 4 [default: 20]
+    --scale <NUMBER>       How many cells wide and tall every mino is, 2 for big mode [default: 1]
+    --colors <MODE>        guideline for a fixed color per piece, or random for a different
+                           random color per piece type every game [default: guideline]
+    --blocks <MODE>        visible, fading for blocks that disappear a while after they lock or
+                           invisible for blocks that disappear as soon as they lock [default: visible]
+    --fade <SECONDS>       How long it takes blocks to disappear in fading [default: 5]
+    --mode <MODE>          marathon, sprint to clear lines as fast as possible, ultra to score
+                           as much as possible before the time runs out, dig to clear garbage
+                           as fast as possible, zen to play without the game ending or master
+                           to survive 20G up to level 999 [default: marathon]
+    --lines <NUMBER>       The number of lines to clear in sprint, like 20, 40 or 100 [default: 40]
+    --time <SECONDS>       How long an ultra game lasts [default: 120]
+    --garbage <NUMBER>     The number of garbage lines to dig through in dig [default: 10]
+    --timings <FILE>       A file with the ARE, line clear delay, DAS and lock delay in frames for
+                           every 100 levels of master, one section a line [default: built in]";
+
+/// The settings that a game is started with, which are read from the command line arguments
+pub struct Config {
+    pub randomizer: Box<dyn Randomizer>,
+    pub seed: Option<u64>,
+    pub das: Duration,
+    pub arr: Duration,
+    pub sdf: u32,
+    pub level: i32,
+    pub goal: Goal,
+    pub width: usize,
+    pub height: usize,
+    pub scale: usize,
+    pub color_mode: ColorMode,
+    pub block_mode: BlockMode,
+    pub mode: Mode,
+    pub timings: Vec<Timing>,
+}
+
+impl Config {
+    /// Returns the config from the command line arguments, or the usage message when the arguments
+    /// couldn't be understood
+    pub fn from_args() -> Result<Self, String> {
+        let mut config = Self {
+            randomizer: randomizer("bag").unwrap(),
+            seed: None,
+            das: Duration::from_millis(167),
+            arr: Duration::from_millis(33),
+            sdf: 6,
+            level: 1,
+            goal: Goal::Fixed,
+            width: 10,
+            height: 20,
+            scale: 1,
+            color_mode: ColorMode::Guideline,
+            block_mode: BlockMode::Visible,
+            mode: Mode::Marathon,
+            timings: MASTER_TIMINGS.to_vec(),
+        };
+        let mut blocks = String::from("visible");
+        let mut fade = 5;
+        let mut mode = String::from("marathon");
+        let mut lines = 40;
+        let mut time = 120;
+        let mut garbage = 10;
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == "--help" || arg == "-h" {
+                return Err(USAGE.to_string());
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing a value for {}\n\n{}", arg, USAGE))?;
+            match arg.as_str() {
+                "--randomizer" => {
+                    config.randomizer = randomizer(&value)
+                        .ok_or_else(|| format!("Unknown randomizer {}\n\n{}", value, USAGE))?;
+                }
+                "--seed" => {
+                    config.seed = Some(parse(&arg, &value)?);
+                }
+                "--das" => {
+                    config.das = Duration::from_millis(parse(&arg, &value)?);
+                }
+                "--arr" => {
+                    config.arr = Duration::from_millis(parse(&arg, &value)?);
+                }
+                "--sdf" => {
+                    config.sdf = parse(&arg, &value)?;
+                }
+                "--level" => {
+                    config.level = parse(&arg, &value)?;
+                    if config.level < 1 {
+                        return Err(format!("The level has to be at least 1\n\n{}", USAGE));
+                    }
+                }
+                "--goal" => {
+                    config.goal = match value.as_str() {
+                        "fixed" => Goal::Fixed,
+                        "variable" => Goal::Variable,
+                        _ => return Err(format!("Unknown goal {}\n\n{}", value, USAGE)),
+                    };
+                }
+                "--width" => {
+                    config.width = parse(&arg, &value)?;
+                    if config.width < 4 {
+                        return Err(format!("The width has to be at least 4\n\n{}", USAGE));
+                    }
+                }
+                "--height" => {
+                    config.height = parse(&arg, &value)?;
+                    if config.height < 4 {
+                        return Err(format!("The height has to be at least 4\n\n{}", USAGE));
+                    }
+                }
+                "--scale" => {
+                    config.scale = parse(&arg, &value)?;
+                    if config.scale < 1 {
+                        return Err(format!("The scale has to be at least 1\n\n{}", USAGE));
+                    }
+                }
+                "--colors" => {
+                    config.color_mode = match value.as_str() {
+                        "guideline" => ColorMode::Guideline,
+                        "random" => ColorMode::Random,
+                        _ => return Err(format!("Unknown color mode {}\n\n{}", value, USAGE)),
+                    };
+                }
+                "--blocks" => {
+                    blocks = value;
+                }
+                "--fade" => {
+                    fade = parse(&arg, &value)?;
+                }
+                "--mode" => {
+                    mode = value;
+                }
+                "--lines" => {
+                    lines = parse(&arg, &value)?;
+                    if lines < 1 {
+                        return Err(format!("The lines have to be at least 1\n\n{}", USAGE));
+                    }
+                }
+                "--time" => {
+                    time = parse(&arg, &value)?;
+                    if time < 1 {
+                        return Err(format!("The time has to be at least 1\n\n{}", USAGE));
+                    }
+                }
+                "--garbage" => {
+                    garbage = parse(&arg, &value)?;
+                    if garbage < 1 {
+                        return Err(format!("The garbage has to be at least 1\n\n{}", USAGE));
+                    }
+                }
+                "--timings" => {
+                    let text = std::fs::read_to_string(&value)
+                        .map_err(|err| format!("Couldn't read {}: {}\n\n{}", value, err, USAGE))?;
+                    config.timings = parse_timings(&text).map_err(|err| {
+                        format!("Invalid timings in {}: {}\n\n{}", value, err, USAGE)
+                    })?;
+                }
+                _ => return Err(format!("Unknown option {}\n\n{}", arg, USAGE)),
+            }
+        }
+
+        if !config.width.is_multiple_of(config.scale) || !config.height.is_multiple_of(config.scale)
+        {
+            return Err(format!(
+                "The width and height have to be multiples of the scale\n\n{}",
+                USAGE
+            ));
+        }
+        if config.width / config.scale < 4 || config.height / config.scale < 4 {
+            return Err(format!(
+                "The board has to fit at least 4 minos across and down\n\n{}",
+                USAGE
+            ));
+        }
+
+        config.block_mode = match blocks.as_str() {
+            "visible" => BlockMode::Visible,
+            "fading" => BlockMode::Fading(Duration::from_secs(fade)),
+            "invisible" => BlockMode::Invisible,
+            _ => return Err(format!("Unknown block mode {}\n\n{}", blocks, USAGE)),
+        };
+        config.mode = match mode.as_str() {
+            "marathon" => Mode::Marathon,
+            "sprint" => Mode::Sprint(lines),
+            "ultra" => Mode::Ultra(Duration::from_secs(time)),
+            "dig" => Mode::Dig(garbage),
+            "zen" => Mode::Zen,
+            "master" => Mode::Master,
+            _ => return Err(format!("Unknown mode {}\n\n{}", mode, USAGE)),
+        };
+
+        Ok(config)
+    }
+}
+
+/// Parses the value given to an option
+fn parse<T: std::str::FromStr>(arg: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value {} for {}\n\n{}", value, arg, USAGE))
+}
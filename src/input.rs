@@ -0,0 +1,256 @@
+use crate::command::Command;
+use std::time::{Duration, Instant};
+
+/// Terminals repeat the events of a held key much faster than anyone can tap it, so two events
+/// that come closer together than this means the key is being held down
+const REPEAT_GAP: Duration = Duration::from_millis(100);
+
+/// The longest a terminal waits before it starts repeating a held key. Events further apart than
+/// this are separate presses.
+const REPEAT_DELAY: Duration = Duration::from_millis(1000);
+
+/// Delayed Auto Shift, Auto Repeat Rate and Soft Drop Factor handling for the movement keys
+///
+/// Terminals only report key presses, and keep repeating them at their own rate while the key is
+/// held, so the key releases have to be figured out from those repeats. A key counts as held once
+/// its events come in quicker than REPEAT_GAP twice in a row, and as released once they stop for
+/// that long. The terminal's own repeats are then ignored, and the held key is repeated at the DAS
+/// and ARR given here instead. Fast taps can look the same as a held key, so if the events never
+/// paused for the terminal's repeat delay, the ignored ones are still applied when the key is let
+/// go before it started repeating.
+///
+/// The ARR and SDF are the same on every terminal, but the DAS isn't. A held key can't be told
+/// apart from a tap until the terminal starts repeating it, so the DAS can't be shorter than the
+/// terminal's repeat delay, and a shorter DAS acts like the repeat delay.
+pub struct AutoRepeat {
+    das: Duration,
+    arr: Duration,
+    sdf: u32,
+    key: Option<Key>,
+}
+
+/// The movement key that was pressed last
+struct Key {
+    command: Command,
+    pressed: Instant,
+    last_event: Instant,
+    held: bool,
+
+    // How many events in a row came in quicker than REPEAT_GAP, and how many were ignored since
+    quick_events: u32,
+    ignored: u32,
+
+    // Whether every event since the press came in quicker than REPEAT_GAP. The terminal waits out
+    // its repeat delay before the first repeat of a held key, so only fast taps look like this.
+    tapping: bool,
+
+    // When auto repeating starts and how many times it repeated since then
+    repeat_from: Option<Instant>,
+    repeats: u32,
+}
+
+impl AutoRepeat {
+    /// Creates the handler with the DAS, the ARR and the SDF, which is how many times faster than
+    /// gravity a soft drop is. An ARR of zero moves the piece all the way to the wall.
+    pub fn new(das: Duration, arr: Duration, sdf: u32) -> Self {
+        Self {
+            das,
+            arr,
+            sdf,
+            key: None,
+        }
+    }
+
+    /// Changes the DAS, which master mode does as the game speeds up
+    pub fn set_das(&mut self, das: Duration) {
+        self.das = das;
+    }
+
+    /// Records a key event for one of the movement commands that came in at the given time. Returns
+    /// whether the event should move the piece, which is the case for presses but not for the
+    /// terminal repeating a held key.
+    pub fn press(&mut self, command: Command, now: Instant) -> bool {
+        match &mut self.key {
+            Some(key) if key.command == command && now - key.last_event <= REPEAT_DELAY => {
+                if now - key.last_event <= REPEAT_GAP {
+                    key.quick_events += 1;
+                } else {
+                    key.quick_events = 0;
+                    key.tapping = false;
+                }
+                // A single quick event could be two fast taps, so the key only counts as held
+                // once the terminal's repeat rate has been seen twice
+                if key.quick_events >= 2 {
+                    key.held = true;
+                }
+                key.last_event = now;
+                if key.held {
+                    key.ignored += 1;
+                }
+                !key.held
+            }
+            _ => {
+                self.key = Some(Key {
+                    command,
+                    pressed: now,
+                    last_event: now,
+                    held: false,
+                    quick_events: 0,
+                    tapping: true,
+                    ignored: 0,
+                    repeat_from: None,
+                    repeats: 0,
+                });
+                true
+            }
+        }
+    }
+
+    /// Starts counting the repeats of the held key over again, which is done when a new piece
+    /// spawns. The key stays charged, so a key held through the entry delay moves the new piece
+    /// at the ARR right away, without making up for the repeats it missed while there was no piece.
+    pub fn rebase(&mut self) {
+        if let Some(key) = &mut self.key {
+            key.repeat_from = None;
+            key.repeats = 0;
+            key.ignored = 0;
+        }
+    }
+
+    /// Checks whether the held key was released by the given time, which has to happen every
+    /// frame, even when there is no piece to move. Returns the taps that were missed if the key
+    /// was really being tapped and was let go before it started repeating.
+    pub fn release(&mut self, now: Instant) -> Option<(Command, u32)> {
+        let key = self.key.as_ref()?;
+        if !key.held || now - key.last_event <= REPEAT_GAP {
+            return None;
+        }
+
+        // The terminal stopped repeating, so the key was released. If it never got to repeat and
+        // the events never paused for the terminal's repeat delay, the ignored events were really
+        // taps, so they still move the piece. Otherwise they were the terminal repeating the key
+        // while the DAS was charging.
+        let command = key.command;
+        let missed = if key.repeats == 0 && key.tapping {
+            key.ignored
+        } else {
+            0
+        };
+        self.key = None;
+        if missed > 0 {
+            Some((command, missed))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the held movement command and how many times it should be repeated at the given
+    /// time.
+    ///
+    /// Left and right start repeating once the key has been held for the DAS and then repeat every
+    /// ARR. Down repeats straight away, at the gravity interval divided by the SDF.
+    pub fn repeats(&mut self, gravity: Duration, now: Instant) -> Option<(Command, u32)> {
+        if let Some(missed) = self.release(now) {
+            return Some(missed);
+        }
+        let key = self.key.as_mut()?;
+        if !key.held {
+            return None;
+        }
+
+        let (delay, rate) = match key.command {
+            Command::Down => (Duration::from_millis(0), gravity / self.sdf.max(1)),
+            _ => (self.das, self.arr),
+        };
+
+        // Repeats are only counted from when the hold is noticed, so a slow terminal doesn't cause
+        // a burst of moves
+        let start = std::cmp::max(key.pressed + delay, now);
+        let repeat_from = *key.repeat_from.get_or_insert(start);
+        if now < repeat_from {
+            return None;
+        }
+        if rate.as_millis() == 0 {
+            return Some((key.command, u32::MAX));
+        }
+
+        let due = ((now - repeat_from).as_millis() / rate.as_millis()) as u32 + 1;
+        let repeats = due - key.repeats;
+        key.repeats = due;
+        Some((key.command, repeats))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRAVITY: Duration = Duration::from_millis(1000);
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    /// Feeds the handler the events a terminal sends for a key held from the start until the
+    /// given time, with the given repeat delay and a repeat every 30 ms after that. Returns how
+    /// many of the events moved the piece.
+    fn hold(auto_repeat: &mut AutoRepeat, start: Instant, delay: u64, until: u64) -> u32 {
+        let mut moves = 0;
+        let mut at = 0;
+        while at <= until {
+            if auto_repeat.press(Command::Left, start + ms(at)) {
+                moves += 1;
+            }
+            at = if at == 0 { delay } else { at + 30 };
+        }
+        moves
+    }
+
+    #[test]
+    fn quick_taps_all_move() {
+        let mut auto_repeat = AutoRepeat::new(ms(167), ms(33), 6);
+        let start = Instant::now();
+        assert!(auto_repeat.press(Command::Left, start));
+        assert!(auto_repeat.press(Command::Left, start + ms(80)));
+        assert!(auto_repeat.repeats(GRAVITY, start + ms(100)).is_none());
+        assert!(auto_repeat.release(start + ms(500)).is_none());
+    }
+
+    #[test]
+    fn missed_taps_are_applied_on_release() {
+        let mut auto_repeat = AutoRepeat::new(ms(1000), ms(33), 6);
+        let start = Instant::now();
+        let moves = (0..5)
+            .filter(|&tap| auto_repeat.press(Command::Left, start + ms(tap * 60)))
+            .count();
+        assert_eq!(moves, 2);
+        assert!(auto_repeat.repeats(GRAVITY, start + ms(260)).is_none());
+        assert!(auto_repeat.release(start + ms(400)) == Some((Command::Left, 3)));
+    }
+
+    #[test]
+    fn hold_shorter_than_the_das_only_moves_for_the_events_before_the_hold() {
+        let mut auto_repeat = AutoRepeat::new(ms(1000), ms(33), 6);
+        let start = Instant::now();
+        // The press and the first two repeats can't be told apart from taps yet
+        assert_eq!(hold(&mut auto_repeat, start, 500, 800), 3);
+        assert!(auto_repeat.repeats(GRAVITY, start + ms(800)).is_none());
+        // The terminal's repeats while the DAS was charging aren't replayed as taps
+        assert!(auto_repeat.repeats(GRAVITY, start + ms(950)).is_none());
+        assert!(auto_repeat.release(start + ms(1200)).is_none());
+    }
+
+    #[test]
+    fn hold_longer_than_the_das_repeats_at_the_arr() {
+        let mut auto_repeat = AutoRepeat::new(ms(167), ms(33), 6);
+        let start = Instant::now();
+        assert_eq!(hold(&mut auto_repeat, start, 250, 310), 3);
+        assert!(auto_repeat.repeats(GRAVITY, start + ms(310)) == Some((Command::Left, 1)));
+        hold(&mut auto_repeat, start + ms(340), 30, 30);
+        assert!(auto_repeat.repeats(GRAVITY, start + ms(376)) == Some((Command::Left, 2)));
+
+        // Once the terminal stops repeating, the key is released and stops moving
+        assert!(auto_repeat.repeats(GRAVITY, start + ms(500)).is_none());
+        assert!(auto_repeat.key.is_none());
+    }
+}
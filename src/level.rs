@@ -0,0 +1,196 @@
+use std::time::Duration;
+
+/// How many lines have to be cleared to move on to the next level
+#[derive(Clone, Copy)]
+pub enum Goal {
+    /// Every level takes 10 lines
+    Fixed,
+    /// Every level takes 5 times the level number in lines, so level 1 takes 5 and level 2 takes 10
+    Variable,
+}
+
+/// Returns the level a game is at after clearing the given number of lines
+pub fn level(start: i32, lines: i32, goal: Goal) -> i32 {
+    match goal {
+        Goal::Fixed => start + lines / 10,
+        Goal::Variable => {
+            let mut level = start;
+            let mut remaining = lines - 5 * level;
+            while remaining >= 0 {
+                level += 1;
+                remaining -= 5 * level;
+            }
+            level
+        }
+    }
+}
+
+/// Returns how long it takes gravity to move the piece down one row at the given level
+///
+/// This is the guideline formula, (0.8 - (level - 1) * 0.007) ^ (level - 1) seconds. It stops
+/// getting faster after level 20, where pieces are already falling about a row every millisecond.
+pub fn gravity(level: i32) -> Duration {
+    let level = level.clamp(1, 20) as f64;
+    Duration::from_secs_f64((0.8 - (level - 1.0) * 0.007).powf(level - 1.0))
+}
+
+/// How long a frame is. The game runs at 60 frames a second, the same as the arcade games that the
+/// master mode timings come from.
+pub const FRAME: Duration = Duration::from_nanos(16_666_667);
+
+/// Returns how long the given number of frames takes
+pub fn frames(frames: u32) -> Duration {
+    FRAME * frames
+}
+
+/// The delays that master mode uses for a section of 100 levels, all counted in frames
+#[derive(Clone, Copy)]
+pub struct Timing {
+    /// ARE, the entry delay between a piece locking and the next piece appearing
+    pub are: u32,
+    /// How much longer the next piece takes to appear when the piece cleared lines
+    pub line_clear: u32,
+    /// Delayed Auto Shift
+    pub das: u32,
+    /// How long a piece can sit on the stack before it locks
+    pub lock_delay: u32,
+}
+
+/// The default master mode timings for every section, starting with levels 0 to 99. The last
+/// section is used for every level after it. Gravity is always 20G in master mode, so these are
+/// what make the game harder. They can be replaced with a timings file, see `parse_timings`.
+pub static MASTER_TIMINGS: [Timing; 6] = [
+    Timing {
+        are: 18,
+        line_clear: 12,
+        das: 12,
+        lock_delay: 30,
+    },
+    Timing {
+        are: 14,
+        line_clear: 6,
+        das: 12,
+        lock_delay: 26,
+    },
+    Timing {
+        are: 14,
+        line_clear: 6,
+        das: 11,
+        lock_delay: 22,
+    },
+    Timing {
+        are: 8,
+        line_clear: 6,
+        das: 10,
+        lock_delay: 18,
+    },
+    Timing {
+        are: 7,
+        line_clear: 5,
+        das: 8,
+        lock_delay: 15,
+    },
+    Timing {
+        are: 6,
+        line_clear: 4,
+        das: 8,
+        lock_delay: 15,
+    },
+];
+
+/// The level that a master mode game is won at
+pub const MASTER_MAX_LEVEL: i32 = 999;
+
+/// Returns the master mode timings for the given level from the table of timings for every
+/// section, which can't be empty
+pub fn master_timing(timings: &[Timing], level: i32) -> Timing {
+    let section = (level / 100).max(0) as usize;
+    timings[section.min(timings.len() - 1)]
+}
+
+/// Parses a master mode timings file
+///
+/// Every line is one section of 100 levels, starting with levels 0 to 99, and holds the ARE, line
+/// clear delay, DAS and lock delay in frames, separated by spaces. Empty lines and anything after
+/// a # are skipped.
+pub fn parse_timings(text: &str) -> Result<Vec<Timing>, String> {
+    let mut timings = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let frames = line
+            .split_whitespace()
+            .map(|value| value.parse())
+            .collect::<Result<Vec<u32>, _>>()
+            .map_err(|_| {
+                format!(
+                    "Line {} has a value that isn't a number of frames",
+                    number + 1
+                )
+            })?;
+        match frames[..] {
+            [are, line_clear, das, lock_delay] => timings.push(Timing {
+                are,
+                line_clear,
+                das,
+                lock_delay,
+            }),
+            _ => {
+                return Err(format!(
+                    "Line {} needs 4 values, the ARE, line clear delay, DAS and lock delay",
+                    number + 1
+                ))
+            }
+        }
+    }
+    if timings.is_empty() {
+        return Err(String::from("There are no sections in the timings"));
+    }
+    Ok(timings)
+}
+
+/// Returns the master mode level after a piece locks and clears the given number of lines
+///
+/// Every piece and every line cleared adds one level. The last level of every section and the
+/// level before the end can only be passed by clearing lines, though.
+pub fn master_level(level: i32, lines: i32) -> i32 {
+    let stopped = level % 100 == 99 || level == MASTER_MAX_LEVEL - 1;
+    let piece = if stopped { 0 } else { 1 };
+    (level + piece + lines).min(MASTER_MAX_LEVEL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timings_file_has_a_section_per_line() {
+        let timings =
+            parse_timings("# are clear das lock\n18 12 12 30\n\n6 4 8 15 # last\n").unwrap();
+        assert_eq!(timings.len(), 2);
+        assert_eq!(master_timing(&timings, 99).das, 12);
+        assert_eq!(master_timing(&timings, 100).are, 6);
+        assert_eq!(master_timing(&timings, 900).lock_delay, 15);
+    }
+
+    #[test]
+    fn timings_file_matches_the_default_timings() {
+        let timings = parse_timings(include_str!("../timings.txt")).unwrap();
+        assert_eq!(timings.len(), MASTER_TIMINGS.len());
+        for (file, default) in timings.iter().zip(MASTER_TIMINGS.iter()) {
+            assert_eq!(file.are, default.are);
+            assert_eq!(file.line_clear, default.line_clear);
+            assert_eq!(file.das, default.das);
+            assert_eq!(file.lock_delay, default.lock_delay);
+        }
+    }
+
+    #[test]
+    fn timings_file_needs_four_numbers_a_line() {
+        assert!(parse_timings("18 12 12").is_err());
+        assert!(parse_timings("18 12 12 thirty").is_err());
+        assert!(parse_timings("# nothing\n").is_err());
+    }
+}
@@ -1,10 +1,20 @@
-use board::Board;
-use color::{random_color, PaintType};
+use board::{Board, GameOverReason};
+use color::{BlockMode, PaintType, Palette, GHOST_COLOR};
 use command::{match_key, Command};
+use config::Config;
 use crossterm::event::{poll, read, Event};
 use crossterm::style::{self, Color};
 use crossterm::{cursor, QueueableCommand};
-use piece::{get_piece, random_piece, rotate, Piece};
+use input::AutoRepeat;
+use level::{Goal, Timing};
+use mode::{format_time, Ending, Mode};
+use piece::{
+    get_piece, kicks, kind, rotate, rotate_180, rotate_ccw, spawn_orientation, t_corners, Corners,
+    Piece, Randomizer,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use score::{Scorer, Spin};
 use std::collections::VecDeque;
 use std::io::{stdout, Stdout, Write};
 use std::time::Duration;
@@ -12,27 +22,35 @@ use std::time::Duration;
 mod board;
 mod color;
 mod command;
+mod config;
+mod input;
+mod level;
+mod mode;
 mod piece;
-
-const TOTAL_WIDTH: u16 = 36;
-
-const GAME_WIDTH: u16 = 12;
+mod score;
 
 const GAME_BORDER_WIDTH: u16 = 1;
 const INFO_PADDING: u16 = 1;
 const INFO_WIDTH: u16 = 4;
 
-const TOTAL_HEIGHT: u16 = 22;
-
 const EMPTY_TOP_INFO_ROWS: u16 = 2;
-const INFO_HEIGHT: u16 = 15; // TODO: Determine if this is the right number
+const INFO_HEIGHT: u16 = 12;
+
+const HOLD_ROW: u16 = EMPTY_TOP_INFO_ROWS + INFO_HEIGHT + INFO_PADDING;
+const INFO_TOTAL_HEIGHT: u16 = HOLD_ROW + PIECE_HEIGHT;
 
 const PIECE_HEIGHT: u16 = 4;
 
+const STATS_WIDTH: usize = 24;
+
+// Since terminal characters are a 2:1 height:width ratio,
+//  2 characters will be used to create a square pixel
+const COLUMN_MULTIPLIER: u16 = 2;
+
 /*
  * Width consists of
  * 1 border = GAME_BORDER_WIDTH
- * 10 game
+ * board width game, 10 by default
  * 1 border = GAME_BORDER_WIDTH
  * 1 padding = INFO_PADDING
  * 4 info box = INFO_WIDTH (piece width)
@@ -40,34 +58,83 @@ const PIECE_HEIGHT: u16 = 4;
  */
 
 /*
- * Game Height = TOTAL_HEIGHT
+ * Game Height
  * 1 border = GAME_BORDER_WIDTH
- * 20 game
+ * board height game, 20 by default
  * 1 border = GAME_BORDER_WIDTH
  */
 
 /*
- * Info Height
+ * Info Height = INFO_TOTAL_HEIGHT
  * 2 padding = EMPTY_TOP_INFO_ROWS
  * 4 piece = PIECE_HEIGHT
  * 4 piece = PIECE_HEIGHT
  * 4 piece = PIECE_HEIGHT
  * 1 padding = INFO_PADDING
- * REST: TODO: RANDOM INFO STUFF?
+ * 4 hold piece = PIECE_HEIGHT
+ *
+ * The stats are printed as text to the right of the info box, starting at stats_column
  */
-const STARTING_ROW: i16 = 0;
-const STARTING_COLUMN: i16 = 4;
+// Pieces spawn with their blocks in rows -1 and 0, which are the bottom of the hidden buffer zone
+const STARTING_ROW: i16 = -2;
+
+/// The index of the last SRS kick test for quarter turns
+const SRS_LAST_KICK: usize = 4;
+
+/// How long line clears and T-spins are called out for
+const CALLOUT_DURATION: Duration = Duration::from_secs(2);
+
+/// How long a piece can sit on the stack before it gets fixed to the board, except in master mode
+/// where it comes from the timings of the section
+const LOCK_DELAY: Duration = Duration::from_millis(500);
+/// How many times moving or rotating a piece on the stack can restart the lock delay
+const LOCK_RESETS: u32 = 15;
+
+/// How long the whole stack is shown for after a line clear, when blocks are hidden
+const REVEAL_DURATION: Duration = Duration::from_secs(1);
+/// How often the board is repainted when blocks are hidden, so fading blocks disappear on time
+const REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How many rows of garbage dig mode keeps on the board, at most half of the board's height
+const DIG_HEIGHT: usize = 10;
+
+/// Mixed into the seed for the garbage rng, so the holes don't follow the same sequence as the
+/// pieces
+const GARBAGE_SALT: u64 = 0x6761_7262_6167_6521;
+
+/// The line of the stats that the timer is shown on
+const TIMER_LINE: u16 = 3;
+
+/// How long the locked stack stays on the screen when the game is over
+const GAME_OVER_DELAY: Duration = Duration::from_secs(1);
 
 #[derive(Clone)]
 struct Point(u16, u16);
 
 struct App {
     board: Board,
+    randomizer: Box<dyn Randomizer>,
+    palette: Palette,
+    block_mode: BlockMode,
+    revealed_until: Option<std::time::Instant>,
+    refreshed: std::time::Instant,
+    auto_repeat: AutoRepeat,
+    rng: StdRng,
+    garbage_rng: StdRng,
+    seed: u64,
     pieces: VecDeque<(Piece, Color)>,
     temp: Vec<Point>,
+    scorer: Scorer,
     score: i32,
     lines: i32,
     level: i32,
+    start_level: i32,
+    goal: Goal,
+    gravity: Duration,
+    mode: Mode,
+    pieces_placed: u32,
+    garbage_added: i32,
+    garbage_cleared: i32,
     stdout: Stdout,
 
     now: std::time::Instant,
@@ -75,83 +142,335 @@ struct App {
     r: i16,
     c: i16,
     color: Color,
+
+    hold: Option<(Piece, Color)>,
+    can_hold: bool,
+
+    lock_started: Option<std::time::Instant>,
+    lock_resets: u32,
+    lowest_row: i16,
+    lock_delay: Duration,
+
+    // The master mode timings for every section of 100 levels
+    timings: Vec<Timing>,
+
+    // When the next piece appears, while waiting out the entry delay after a piece locked
+    spawn_at: Option<std::time::Instant>,
+
+    // Whether the last action was a rotation, and if so whether it was a quarter turn that needed
+    // the last SRS kick test
+    last_rotation: Option<bool>,
+    callout: Option<(Vec<String>, std::time::Instant)>,
+
+    started: std::time::Instant,
+    // How the game ended, along with the time it was played for
+    ending: Option<(Ending, Duration)>,
 }
 
 impl App {
     /// Returns the next piece out of the deque and replaces it with a new piece
     fn next_piece(&mut self) -> (Piece, Color) {
         let sol = self.pieces.pop_front();
-        self.pieces.push_back((random_piece(), random_color()));
+        let piece = self.randomizer.draw(&mut self.rng);
+        let color = self.palette.color(kind(piece));
+        self.pieces.push_back((piece, color));
         sol.unwrap()
     }
 
-    /// Gets the players move and updates the board accordingly
+    /// Makes the given piece the active piece at the top of the board
+    ///
+    /// Pieces spawn in the two rows right above the visible board and then drop down one row
+    /// straight away if there is room, or all the way down with 20G gravity. When the piece
+    /// overlaps the stack where it spawns, the game is over. Every new piece, including one swapped
+    /// in from hold, gets a full gravity interval before it falls.
+    fn spawn_piece(&mut self, piece: Piece, color: Color) -> crossterm::Result<()> {
+        self.now = std::time::Instant::now();
+        self.r = STARTING_ROW;
+        self.c = self.spawn_column();
+        self.piece = piece;
+        self.color = color;
+
+        self.lock_started = None;
+        self.lock_resets = 0;
+        self.lowest_row = STARTING_ROW;
+        self.last_rotation = None;
+
+        if self.board.detect_collision(self.piece, self.r, self.c) {
+            self.top_out(GameOverReason::BlockOut)?;
+            if self.ending.is_some() {
+                return Ok(());
+            }
+        }
+        if !self.board.detect_collision(self.piece, self.r + 1, self.c) {
+            self.r += 1;
+        }
+        if self.gravity.is_zero() {
+            self.r = self.ghost_row();
+            self.update_lock_delay();
+        }
+        Ok(())
+    }
+
+    /// Handles the player topping out. In zen mode the board is cleared and play carries on,
+    /// otherwise the game is over.
+    fn top_out(&mut self, reason: GameOverReason) -> crossterm::Result<()> {
+        if let Mode::Zen = self.mode {
+            self.board.clear();
+            self.paint_board(self.board.height as u16)?;
+            self.callout = Some((vec![String::from("TOP OUT")], std::time::Instant::now()));
+            self.paint_stats()
+        } else {
+            self.game_over(reason)
+        }
+    }
+
+    /// Ends the game because the player topped out
+    ///
+    /// The stack is locked and left on the screen for a moment first, so the player can see how
+    /// the game ended.
+    fn game_over(&mut self, reason: GameOverReason) -> crossterm::Result<()> {
+        self.end(Ending::GameOver(reason));
+        self.board.lock();
+        self.paint_board(self.board.height as u16)?;
+        self.stdout.flush()?;
+        std::thread::sleep(GAME_OVER_DELAY);
+        Ok(())
+    }
+
+    /// Stops the game and the clock. The game loop finishes once the game has an ending.
+    fn end(&mut self, ending: Ending) {
+        if self.ending.is_none() {
+            self.ending = Some((ending, self.started.elapsed()));
+        }
+    }
+
+    /// Returns how long the game has been played for, which stops counting once the game ends
+    fn elapsed(&self) -> Duration {
+        match self.ending {
+            Some((_, time)) => time,
+            None => self.started.elapsed(),
+        }
+    }
+
+    /// Starts the lock delay when the piece touches the stack, and stops it when the piece is
+    /// moved off of the stack. Reaching a new lowest row gives the piece all of its resets back.
+    fn update_lock_delay(&mut self) {
+        if self.r > self.lowest_row {
+            self.lowest_row = self.r;
+            self.lock_resets = 0;
+        }
+
+        let grounded = self.board.detect_collision(self.piece, self.r + 1, self.c);
+        match (grounded, self.lock_started) {
+            (true, None) => self.lock_started = Some(std::time::Instant::now()),
+            (false, Some(_)) => self.lock_started = None,
+            _ => {}
+        }
+    }
+
+    /// Restarts the lock delay after a successful move or rotation while the piece is on the
+    /// stack, as long as it hasn't used up all of its resets
+    fn reset_lock_delay(&mut self) {
+        if self.lock_started.is_some() && self.lock_resets < LOCK_RESETS {
+            self.lock_resets += 1;
+            self.lock_started = Some(std::time::Instant::now());
+        }
+        self.update_lock_delay();
+    }
+
+    /// Returns whether or not the piece has been on the stack for long enough to be fixed to the
+    /// board. Once every reset is used up, the piece locks as soon as it touches the stack.
+    fn lock_expired(&self) -> bool {
+        match self.lock_started {
+            Some(started) => {
+                started.elapsed() >= self.lock_delay || self.lock_resets >= LOCK_RESETS
+            }
+            None => false,
+        }
+    }
+
+    /// Swaps the active piece with the one in the hold slot. When the slot is empty, the active
+    /// piece is stored and the next piece from the queue is used instead.
+    ///
+    /// Holding is only allowed once per piece, until that piece is fixed to the board.
+    fn hold_piece(&mut self) -> crossterm::Result<()> {
+        if !self.can_hold {
+            return Ok(());
+        }
+        self.can_hold = false;
+
+        let held = (spawn_orientation(self.piece), self.color);
+        let (piece, color) = match self.hold.replace(held) {
+            Some(swapped) => swapped,
+            None => {
+                let next = self.next_piece();
+                self.clear_next_piece()?;
+                self.paint_next_piece()?;
+                next
+            }
+        };
+        self.spawn_piece(piece, color)?;
+
+        self.clear_hold_piece()?;
+        self.paint_hold_piece()
+    }
+
+    /// Returns whether the active piece was spun into place, using the 3-corner rule
     ///
-    /// For rotations, the piece moves to the next valid rotation, This means that if no valid
-    /// rotations are found, the piece will just rotate back into it's original orientation.
-    fn update_player_move(&mut self) -> crossterm::Result<()> {
-        if poll(Duration::from_millis(25))? {
+    /// The last thing done to the piece has to be a rotation and the piece has to be a T. When 3 of
+    /// the 4 corners around the middle of the T are filled, it's a T-spin if both of the corners
+    /// the T is pointing towards are filled, otherwise it's a T-spin mini. A mini gets upgraded to
+    /// a T-spin when the last kick test of a quarter turn was needed, since that kick is what makes
+    /// T-spin triples. 180 degree turns have their own kicks, which never upgrade a mini.
+    fn detect_spin(&self) -> Spin {
+        let last_test = match self.last_rotation {
+            Some(last_test) if spawn_orientation(self.piece) == Piece::T => last_test,
+            _ => return Spin::None,
+        };
+
+        let (front, back) = t_corners(self.piece);
+        let filled = |corners: Corners| {
+            corners
+                .iter()
+                .filter(|&&(r, c)| self.board.is_occupied(self.r + r, self.c + c))
+                .count()
+        };
+        let (front, back) = (filled(front), filled(back));
+
+        if front + back < 3 {
+            Spin::None
+        } else if front == 2 || last_test {
+            Spin::Full
+        } else {
+            Spin::Mini
+        }
+    }
+
+    /// Rotates the piece into the given orientation using the SRS kick tests
+    ///
+    /// Each kick is tried in order and the first one that doesn't collide is used. Returns whether
+    /// or not the rotation happened, if every kick fails the piece stays where it is. A successful
+    /// rotation restarts the lock delay.
+    fn rotate_piece(&mut self, rotated: Piece) -> bool {
+        let quarter_turn = rotated == rotate(self.piece) || rotated == rotate_ccw(self.piece);
+        for (kick, &(x, y)) in kicks(self.piece, rotated).iter().enumerate() {
+            // Kicks use an upwards y axis, while rows grow downwards
+            if !self.board.detect_collision(rotated, self.r - y, self.c + x) {
+                self.piece = rotated;
+                self.r -= y;
+                self.c += x;
+                self.last_rotation = Some(quarter_turn && kick == SRS_LAST_KICK);
+                self.reset_lock_delay();
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Moves the piece one cell to the left, right or down. Returns whether or not the piece moved.
+    fn shift(&mut self, command: Command) -> bool {
+        let (row, column) = match command {
+            Command::Left => (self.r, self.c - 1),
+            Command::Right => (self.r, self.c + 1),
+            Command::Down => (self.r + 1, self.c),
+            _ => return false,
+        };
+        if self.board.detect_collision(self.piece, row, column) {
+            return false;
+        }
+
+        self.r = row;
+        self.c = column;
+        self.last_rotation = None;
+        if let Command::Down = command {
+            // Soft drops never lock the piece, it's still up to the lock delay
+            self.score += score::soft_drop(1);
+            self.update_lock_delay();
+        } else {
+            self.reset_lock_delay();
+        }
+        true
+    }
+
+    /// Repeats the held movement key according to the players DAS, ARR and SDF
+    fn update_auto_repeat(&mut self) -> crossterm::Result<()> {
+        if let Some((command, repeats)) = self
+            .auto_repeat
+            .repeats(self.gravity, std::time::Instant::now())
+        {
+            let mut moved = false;
+            for _ in 0..repeats {
+                if !self.shift(command) {
+                    break;
+                }
+                moved = true;
+            }
+            if moved {
+                self.paint_active_piece()?;
+                self.paint_stats()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets the players moves until the end of the frame and updates the board accordingly
+    ///
+    /// For rotations, the SRS kicks are tried. If none of them are valid, the piece stays in its
+    /// current orientation. While waiting for the next piece to appear, only quitting works, but
+    /// the movement keys are still tracked, so they can be held through the wait.
+    fn update_player_move(&mut self, frame_end: std::time::Instant) -> crossterm::Result<()> {
+        while self.ending.is_none()
+            && poll(frame_end.saturating_duration_since(std::time::Instant::now()))?
+        {
             match read()? {
                 Event::Key(event) => {
-                    match match_key(event.code) {
-                        Command::Left => {
-                            if !self.board.detect_collision(self.piece, self.r, self.c - 1) {
-                                self.c -= 1;
-                            }
+                    let command = match_key(event.code);
+                    if self.spawn_at.is_some() && command != Command::Escape {
+                        if matches!(command, Command::Left | Command::Right | Command::Down) {
+                            self.auto_repeat.press(command, std::time::Instant::now());
                         }
-                        Command::Right => {
-                            if !self.board.detect_collision(self.piece, self.r, self.c + 1) {
-                                self.c += 1;
-                            }
+                        continue;
+                    }
+                    match command {
+                        command @ (Command::Left | Command::Right | Command::Down)
+                            if self.auto_repeat.press(command, std::time::Instant::now()) =>
+                        {
+                            self.shift(command);
                         }
-                        Command::Down => {
-                            if !self.board.detect_collision(self.piece, self.r + 1, self.c) {
-                                self.r += 1;
-                            }
+                        Command::Up => {
+                            self.rotate_piece(rotate(self.piece));
+                        }
+                        Command::RotateCcw => {
+                            self.rotate_piece(rotate_ccw(self.piece));
+                        }
+                        Command::Rotate180 => {
+                            self.rotate_piece(rotate_180(self.piece));
                         }
-                        Command::Up => loop {
-                            self.piece = rotate(self.piece);
-                            if !self.board.detect_collision(self.piece, self.r, self.c) {
-                                break;
-                            }
-                        },
                         Command::Space => {
-                            // Places the piece onto the board
-                            loop {
-                                if self.board.detect_collision(self.piece, self.r + 1, self.c) {
-                                    break;
-                                }
-                                self.r += 1;
+                            // Hard drops lock the piece right away, skipping the lock delay
+                            let ghost_row = self.ghost_row();
+                            if ghost_row != self.r {
+                                self.score += score::hard_drop((ghost_row - self.r) as i32);
+                                self.r = ghost_row;
+                                self.last_rotation = None;
                             }
-                            // Ensure that enough time elapsed to make this piece permanent
-                            self.now -= std::time::Duration::new(5, 0);
+                            self.lock_piece()?;
+                        }
+                        Command::Hold => {
+                            self.hold_piece()?;
                         }
                         Command::Escape => {
-                            self.clear_screen()?;
-                            println!("Aborting.. Lines clear: {}.", self.lines);
-                            std::process::exit(0);
+                            self.end(Ending::Quit);
                         }
                         _ => {}
                     }
-                    self.queue_clear_piece()?;
-                    self.paint_piece(
-                        self.piece,
-                        self.r as u16,
-                        self.c as u16,
-                        self.color,
-                        PaintType::Temporary,
-                    )?;
+                    self.paint_active_piece()?;
+                    self.paint_stats()?;
                 }
                 Event::Resize(_, _) => {
                     self.init()?;
 
-                    self.paint_piece(
-                        self.piece,
-                        self.r as u16,
-                        self.c as u16,
-                        self.color,
-                        PaintType::Temporary,
-                    )?;
+                    self.paint_active_piece()?;
                 }
                 _ => {}
             }
@@ -160,76 +479,211 @@ impl App {
         Ok(())
     }
 
-    /// Either moves pieces down due to gravity or affixes piece to the board since, it can no
-    /// longer move downwards. In the latter case, the next piece is setup for the next game loop.
+    /// Moves the piece down due to gravity. Once the piece can no longer move downwards, the lock
+    /// delay decides when it gets fixed to the board.
+    ///
+    /// The piece moves down one row for every gravity interval since the last tick, since faster
+    /// levels can take more than one row a frame. An interval of zero is 20G, which moves the piece
+    /// right down onto the stack.
     fn gravity_tick(&mut self) -> crossterm::Result<()> {
+        let rows = match self
+            .now
+            .elapsed()
+            .as_nanos()
+            .checked_div(self.gravity.as_nanos())
+        {
+            Some(rows) => rows.max(1),
+            None => u128::MAX,
+        };
         self.now = std::time::Instant::now();
 
-        if self.board.detect_collision(self.piece, self.r + 1, self.c) {
-            // Fix piece to board
-            self.temp.clear();
+        let mut moved = false;
+        for _ in 0..rows {
+            if self.board.detect_collision(self.piece, self.r + 1, self.c) {
+                break;
+            }
+            self.r += 1;
+            moved = true;
+        }
+        if moved {
+            self.last_rotation = None;
+            self.paint_active_piece()?;
+        }
+        self.update_lock_delay();
+        Ok(())
+    }
 
-            self.paint_piece(
-                self.piece,
-                self.r as u16,
-                self.c as u16,
-                self.color,
-                PaintType::Permanent,
-            )?;
-            self.board.save(self.piece, self.r, self.c, self.color);
+    /// Affixes the piece to the board and sets up the next piece for the next game loop
+    fn lock_piece(&mut self) -> crossterm::Result<()> {
+        self.temp.clear();
+        let spin = self.detect_spin();
 
-            let new_lines = self.board.handle_completed_lines(self.r);
-            if new_lines > 0 {
-                self.paint_board(self.r as u16 + PIECE_HEIGHT)?;
-                self.lines += new_lines;
-            }
+        // A block that has only just locked is already hidden when blocks are invisible
+        let color = if self.is_hidden(Some(std::time::Instant::now())) {
+            Color::Black
+        } else {
+            self.color
+        };
+        self.paint_piece(
+            self.piece,
+            self.r,
+            self.c,
+            color,
+            PaintType::Permanent,
+            self.board.scale,
+        )?;
+        self.board.save(self.piece, self.r, self.c);
+        self.pieces_placed += 1;
 
-            // Check to see if game ended
-            if self.board.detect_endgame(self.piece, self.r) {
-                self.clear_screen()?;
-                println!("GAME OVER! You cleared {} lines", self.lines);
-                std::process::exit(0);
+        let garbage = self.board.garbage_rows();
+        let new_lines = self.board.handle_completed_lines(self.r);
+        self.garbage_cleared += (garbage - self.board.garbage_rows()) as i32;
+        if new_lines > 0 {
+            self.revealed_until = Some(std::time::Instant::now() + REVEAL_DURATION);
+            self.paint_board((self.r + PIECE_HEIGHT as i16).max(0) as u16)?;
+            self.lines += new_lines;
+        }
+        let perfect_clear = new_lines > 0 && self.board.is_empty();
+        // Master mode starts at level 0, which would make every line clear worth nothing
+        self.score += self
+            .scorer
+            .line_clear(new_lines, spin, perfect_clear, self.level.max(1));
+        if self.mode.has_levels() {
+            self.level = level::level(self.start_level, self.lines, self.goal);
+            self.gravity = level::gravity(self.level);
+        }
+        if let Mode::Master = self.mode {
+            self.level = level::master_level(self.level, new_lines);
+            self.apply_master_timing();
+        }
+
+        let mut callout = vec![self.scorer.last_clear().to_string()];
+        if perfect_clear {
+            callout.push(String::from("PERFECT CLEAR"));
+        }
+        if !callout[0].is_empty() {
+            self.callout = Some((callout, std::time::Instant::now()));
+        }
+        self.paint_stats()?;
+
+        // Check to see if game ended
+        if self.board.detect_lock_out(self.piece, self.r) {
+            self.top_out(GameOverReason::LockOut)?;
+            if self.ending.is_some() {
+                return Ok(());
             }
+        }
+        if let Some(ending) =
+            self.mode
+                .ending(self.lines, self.garbage_cleared, self.level, self.elapsed())
+        {
+            self.end(ending);
+            return Ok(());
+        }
+        self.refill_garbage()?;
+        if self.ending.is_some() {
+            return Ok(());
+        }
 
-            // Setup for next piece
-            self.r = STARTING_ROW;
-            self.c = STARTING_COLUMN;
-            let (new_piece, new_color) = self.next_piece();
-            self.clear_next_piece()?;
-            self.paint_next_piece()?;
-            self.piece = new_piece;
-            self.color = new_color;
-            self.paint_piece(
-                self.piece,
-                self.r as u16,
-                self.c as u16,
-                self.color,
-                PaintType::Temporary,
-            )?;
-            self.stdout.flush()?;
+        // The next piece waits out the entry delay, if there is one
+        self.lock_started = None;
+        let delay = self.entry_delay(new_lines);
+        if delay.is_zero() {
+            self.spawn_next_piece()
         } else {
-            // Gravity
-            self.r += 1;
-            self.queue_clear_piece()?;
-            self.temp.clear();
-            self.paint_piece(
-                self.piece,
-                self.r as u16,
-                self.c as u16,
-                self.color,
-                PaintType::Temporary,
-            )?;
+            self.spawn_at = Some(std::time::Instant::now() + delay);
+            Ok(())
         }
+    }
+
+    /// Makes the next piece from the queue the active piece
+    fn spawn_next_piece(&mut self) -> crossterm::Result<()> {
+        self.spawn_at = None;
+        self.auto_repeat.rebase();
+        let (new_piece, new_color) = self.next_piece();
+        self.clear_next_piece()?;
+        self.paint_next_piece()?;
+        self.spawn_piece(new_piece, new_color)?;
+        self.can_hold = true;
+        self.paint_active_piece()?;
+        self.stdout.flush()?;
         Ok(())
     }
 
+    /// Returns how long the next piece waits before it appears after a piece locked and cleared
+    /// the given number of lines. Only master mode has an entry delay.
+    fn entry_delay(&self, lines: i32) -> Duration {
+        match self.mode {
+            Mode::Master => {
+                let timing = level::master_timing(&self.timings, self.level);
+                let line_clear = if lines > 0 { timing.line_clear } else { 0 };
+                level::frames(timing.are + line_clear)
+            }
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Sets the lock delay and DAS to the master mode timings for the current section
+    fn apply_master_timing(&mut self) {
+        let timing = level::master_timing(&self.timings, self.level);
+        self.lock_delay = level::frames(timing.lock_delay);
+        self.auto_repeat.set_das(level::frames(timing.das));
+    }
+
+    /// Pushes garbage in from the bottom of the board in dig mode, until the board has as many
+    /// garbage rows as dig mode keeps on it or all of the mode's garbage has been added
+    ///
+    /// The garbage is cheese, so every row has its hole in a different column from the row
+    /// below it, including the row that was at the bottom before.
+    fn refill_garbage(&mut self) -> crossterm::Result<()> {
+        let total = match self.mode {
+            Mode::Dig(total) => total,
+            _ => return Ok(()),
+        };
+        let target = std::cmp::min(DIG_HEIGHT, self.board.height / 2) as i32;
+        let on_board = self.garbage_added - self.garbage_cleared;
+        let lines = std::cmp::min(target - on_board, total - self.garbage_added);
+        if lines <= 0 {
+            return Ok(());
+        }
+
+        let width = self.board.width;
+        let hole = match self.board.bottom_hole() {
+            Some(hole) => (hole + self.garbage_rng.gen_range(1..width)) % width,
+            None => self.garbage_rng.gen_range(0..width),
+        };
+        self.garbage_added += lines;
+        if self
+            .board
+            .add_garbage(lines as usize, hole, 1.0, &mut self.garbage_rng)
+        {
+            return self.top_out(GameOverReason::TopOut);
+        }
+        self.paint_board(self.board.height as u16)
+    }
+
+    /// Returns whether or not a block that was locked in place at the given time is hidden. The
+    /// whole stack is shown for a moment after a line clear and for good once the game is over.
+    fn is_hidden(&self, locked_at: Option<std::time::Instant>) -> bool {
+        let revealed = match self.revealed_until {
+            Some(until) => std::time::Instant::now() < until,
+            None => false,
+        };
+        !revealed && self.ending.is_none() && self.block_mode.is_hidden(locked_at)
+    }
+
     /// Repaints the board after a completed row has been deleted
     fn paint_board(&mut self, row: u16) -> crossterm::Result<()> {
-        let row = std::cmp::min(self.board.height, row as usize);
+        let scale = self.board.scale;
+        let row = std::cmp::min(self.board.height, row as usize) * scale;
         let mut color;
         for r in 0..row {
-            for c in 0..self.board.width {
-                color = self.board.color_board[r][c];
+            for c in 0..self.board.width * scale {
+                color = if self.is_hidden(self.board.locked_at(r, c)) {
+                    Color::Black
+                } else {
+                    self.palette.cell_color(self.board.cell(r, c))
+                };
                 self.paint(
                     r as u16 + GAME_BORDER_WIDTH,
                     c as u16 + GAME_BORDER_WIDTH,
@@ -243,53 +697,193 @@ impl App {
 
     /// Runs the program
     ///
-    /// First the initial piece is setup, then the event loop, which looks for a player move and
-    /// gravity ticks
+    /// First the initial piece is setup, then the event loop, which steps through the game a frame
+    /// at a time. Player moves are handled as they come in during the frame, and everything else,
+    /// like gravity ticks, lock delays and entry delays, is checked at the end of every frame.
     fn run(&mut self) -> crossterm::Result<()> {
+        if let Mode::Master = self.mode {
+            self.apply_master_timing();
+        }
         let (piece, color) = self.next_piece();
         self.clear_next_piece()?;
         self.paint_next_piece()?;
-        self.now = std::time::Instant::now();
-        self.piece = piece;
-        self.color = color;
+        self.refill_garbage()?;
+        self.spawn_piece(piece, color)?;
 
-        self.paint_piece(
-            self.piece,
-            self.r as u16,
-            self.c as u16,
-            self.color,
-            PaintType::Temporary,
-        )?;
-        loop {
-            self.update_player_move()?;
+        self.paint_active_piece()?;
+        self.started = std::time::Instant::now();
+        let mut frame_end = self.started + level::FRAME;
+        while self.ending.is_none() {
+            self.update_player_move(frame_end)?;
+            frame_end = std::cmp::max(frame_end, std::time::Instant::now()) + level::FRAME;
 
-            if self.now.elapsed().as_millis() > 500 {
-                self.gravity_tick()?;
+            match self.spawn_at {
+                Some(spawn_at) if spawn_at <= std::time::Instant::now() => {
+                    self.spawn_next_piece()?;
+                }
+                Some(_) => {
+                    // There is no piece to move, but a key that is let go still has to be noticed
+                    self.auto_repeat.release(std::time::Instant::now());
+                }
+                None => {
+                    self.update_auto_repeat()?;
+
+                    if self.now.elapsed() > self.gravity {
+                        self.gravity_tick()?;
+                    }
+
+                    if self.lock_expired() {
+                        self.lock_piece()?;
+                    }
+                }
+            }
+
+            if let Some((_, shown)) = self.callout {
+                if shown.elapsed() > CALLOUT_DURATION {
+                    self.callout = None;
+                    self.paint_stats()?;
+                }
+            }
+
+            if !matches!(self.block_mode, BlockMode::Visible)
+                && self.refreshed.elapsed() > REFRESH_INTERVAL
+            {
+                self.refreshed = std::time::Instant::now();
+                self.paint_board(self.board.height as u16)?;
+                self.paint_active_piece()?;
             }
+
+            if self.mode.is_timed() {
+                if let Some(ending) =
+                    self.mode
+                        .ending(self.lines, self.garbage_cleared, self.level, self.elapsed())
+                {
+                    self.end(ending);
+                }
+                self.paint_timer()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Puts the terminal back the way it was and shows the results of the game
+    fn show_results(&mut self) -> crossterm::Result<()> {
+        let (ending, time) = match self.ending {
+            Some(ending) => ending,
+            None => return Ok(()),
+        };
+
+        self.clear_screen()?;
+        self.stdout
+            .queue(style::ResetColor)?
+            .queue(cursor::MoveTo(0, 0))?
+            .queue(cursor::Show)?;
+        self.stdout.flush()?;
+        crossterm::terminal::disable_raw_mode()?;
+
+        let seconds = time.as_secs_f64();
+        let pps = if seconds > 0.0 {
+            self.pieces_placed as f64 / seconds
+        } else {
+            0.0
+        };
+        println!("{}", self.mode);
+        println!("{}", ending);
+        println!();
+        println!("TIME    {}", format_time(time));
+        println!("SCORE   {}", self.score);
+        println!("LINES   {}", self.lines);
+        println!("LEVEL   {}", self.level);
+        if let Mode::Dig(_) = self.mode {
+            println!("GARBAGE {}", self.garbage_cleared);
         }
+        println!("PIECES  {}", self.pieces_placed);
+        println!("PPS     {:.2}", pps);
+        println!("SEED    {}", self.seed);
+        Ok(())
     }
 
     /// Initializes an App struct
-    fn new() -> Self {
+    fn new(config: Config) -> Self {
+        // Every random decision in the game comes from the seed, so a game can be replayed. The
+        // garbage gets its own rng, since how much garbage is pushed in depends on how the game
+        // is played and it would change the pieces that come after it otherwise.
+        let seed = config
+            .seed
+            .unwrap_or_else(|| rand::thread_rng().gen::<u32>() as u64);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let garbage_rng = StdRng::seed_from_u64(seed ^ GARBAGE_SALT);
+
+        let palette = Palette::new(config.color_mode, &mut rng);
+        let mut randomizer = config.randomizer;
         let mut pieces = VecDeque::with_capacity(3);
         for _ in 0..3 {
-            pieces.push_back((random_piece(), random_color()));
+            let piece = randomizer.draw(&mut rng);
+            pieces.push_back((piece, palette.color(kind(piece))));
         }
 
+        // Master mode always starts at level 0 with 20G gravity
+        let (start_level, gravity) = match config.mode {
+            Mode::Master => (0, Duration::ZERO),
+            _ => (config.level, level::gravity(config.level)),
+        };
+
         Self {
-            board: Board::new(),
-            level: 0,
+            board: Board::new(
+                config.width / config.scale,
+                config.height / config.scale,
+                config.scale,
+            ),
+            randomizer,
+            palette,
+            block_mode: config.block_mode,
+            revealed_until: None,
+            refreshed: std::time::Instant::now(),
+            auto_repeat: AutoRepeat::new(config.das, config.arr, config.sdf),
+            rng,
+            garbage_rng,
+            seed,
+            scorer: Scorer::new(),
+            level: start_level,
+            start_level,
+            goal: config.goal,
+            gravity,
+            mode: config.mode,
+            pieces_placed: 0,
+            garbage_added: 0,
+            garbage_cleared: 0,
             score: 0,
             lines: 0,
             stdout: stdout(),
             pieces,
             temp: vec![],
 
-            piece: random_piece(),
+            // The active piece is dealt from the queue when the game starts in run, so this is
+            // only a placeholder
+            piece: Piece::O,
             now: std::time::Instant::now(),
             r: STARTING_ROW,
-            c: STARTING_COLUMN,
+            c: 0,
             color: Color::Black,
+
+            hold: None,
+            can_hold: true,
+
+            lock_started: None,
+            lock_resets: 0,
+            lowest_row: STARTING_ROW,
+            lock_delay: LOCK_DELAY,
+
+            timings: config.timings,
+
+            spawn_at: None,
+
+            last_rotation: None,
+            callout: None,
+
+            started: std::time::Instant::now(),
+            ending: None,
         }
     }
 
@@ -312,28 +906,84 @@ impl App {
         Ok(())
     }
 
+    /// Returns the row the active piece would land on if it were dropped straight down
+    fn ghost_row(&self) -> i16 {
+        let mut row = self.r;
+        while !self.board.detect_collision(self.piece, row + 1, self.c) {
+            row += 1;
+        }
+        row
+    }
+
+    /// Repaints the active piece along with its ghost, which shows where the piece will land.
+    ///
+    /// Both are painted as Temporary, so the previous rendering is cleared first. The ghost is
+    /// painted first, so the piece is drawn on top when they overlap.
+    fn paint_active_piece(&mut self) -> crossterm::Result<()> {
+        self.queue_clear_piece()?;
+        self.temp.clear();
+        if self.spawn_at.is_some() {
+            // There isn't an active piece until the entry delay is over
+            self.stdout.flush()?;
+            return Ok(());
+        }
+
+        let ghost_row = self.ghost_row();
+        let scale = self.board.scale;
+        self.paint_piece(
+            self.piece,
+            ghost_row,
+            self.c,
+            GHOST_COLOR,
+            PaintType::Temporary,
+            scale,
+        )?;
+        self.paint_piece(
+            self.piece,
+            self.r,
+            self.c,
+            self.color,
+            PaintType::Temporary,
+            scale,
+        )
+    }
+
     /// Paints a piece to the board
+    ///
+    /// Every mino is painted as a square of scale by scale pixels, which is how pieces on the
+    /// board are shown in big mode. The pieces on the info pane always have a scale of 1.
     fn paint_piece(
         &mut self,
         piece: Piece,
-        row: u16,
-        column: u16,
+        row: i16,
+        column: i16,
         color: Color,
         paint_type: PaintType,
+        scale: usize,
     ) -> crossterm::Result<()> {
         let next_piece = get_piece(piece);
+        let border = GAME_BORDER_WIDTH as i16;
+        let scale = scale as i16;
 
-        for r in 0..next_piece.len() {
-            for c in 0..next_piece[0].len() {
-                if next_piece[r][c] == 1 && (row + r as u16) != 0 {
-                    self.paint(row + r as u16, column.wrapping_add(c as u16), color)?;
-                    match paint_type {
-                        PaintType::Temporary => {
-                            self.temp
-                                .push(Point(row + r as u16, column.wrapping_add(c as u16)));
+        for (r, piece_row) in next_piece.iter().enumerate() {
+            for (c, &cell) in piece_row.iter().enumerate() {
+                if cell == 0 {
+                    continue;
+                }
+                let top = (row + r as i16 - border) * scale + border;
+                let left = (column + c as i16 - border) * scale + border;
+                for pixel_row in top..top + scale {
+                    // Anything at or above the top border is hidden
+                    if pixel_row <= 0 {
+                        continue;
+                    }
+                    for pixel_column in left..left + scale {
+                        let point = Point(pixel_row as u16, pixel_column as u16);
+                        self.paint(point.0, point.1, color)?;
+                        if let PaintType::Temporary = paint_type {
+                            self.temp.push(point);
                         }
-                        _ => {}
-                    };
+                    }
                 }
             }
         }
@@ -343,7 +993,7 @@ impl App {
 
     /// Paints the next pieces on the info pane
     fn paint_next_piece(&mut self) -> crossterm::Result<()> {
-        let column = GAME_WIDTH + INFO_PADDING;
+        let column = self.info_column();
         let row = EMPTY_TOP_INFO_ROWS;
 
         for i in 0..self.pieces.len() {
@@ -351,25 +1001,50 @@ impl App {
 
             self.paint_piece(
                 piece,
-                row + (i as u16 * PIECE_HEIGHT),
-                column,
+                (row + i as u16 * PIECE_HEIGHT) as i16,
+                column as i16,
+                color,
+                PaintType::Permanent,
+                1,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Paints the held piece on the info pane, below the next pieces
+    fn paint_hold_piece(&mut self) -> crossterm::Result<()> {
+        if let Some((piece, color)) = self.hold {
+            self.paint_piece(
+                piece,
+                HOLD_ROW as i16,
+                self.info_column() as i16,
                 color,
                 PaintType::Permanent,
+                1,
             )?;
         }
         Ok(())
     }
 
-    /// Clears all of the pieces on the info pane visually, not physically
+    /// Clears all of the next pieces on the info pane visually, not physically
     fn clear_next_piece(&mut self) -> crossterm::Result<()> {
-        let r_start = EMPTY_TOP_INFO_ROWS;
-        let r_end = r_start + INFO_HEIGHT;
-        let c_start = GAME_WIDTH + INFO_PADDING;
+        self.clear_info(EMPTY_TOP_INFO_ROWS, INFO_HEIGHT)
+    }
+
+    /// Clears the held piece on the info pane visually, not physically
+    fn clear_hold_piece(&mut self) -> crossterm::Result<()> {
+        self.clear_info(HOLD_ROW, PIECE_HEIGHT)
+    }
+
+    /// Clears the given rows of the info pane
+    fn clear_info(&mut self, r_start: u16, rows: u16) -> crossterm::Result<()> {
+        let r_end = r_start + rows;
+        let c_start = self.info_column();
         let c_end = c_start + INFO_WIDTH;
 
         for r in r_start..r_end {
             for c in c_start..c_end {
-                self.paint(r as u16, c as u16, Color::Black)?;
+                self.paint(r, c, Color::Black)?;
             }
         }
         self.stdout.flush()?;
@@ -381,25 +1056,10 @@ impl App {
     /// Since the screen has a ratio of 2:1, it's necessary to paint 2 characters to get a square
     /// pixel
     fn paint(&mut self, row: u16, column: u16, color: Color) -> crossterm::Result<()> {
-        let (width, height) =
-            crossterm::terminal::size().expect("Could not get terminal dimensions.");
+        let (game_multiplier, info_multiplier) = self.multipliers();
+        let game_width = self.game_width();
 
-        // Keep a 2:1 ratio between the game section and the info section
-        let mut width_multiplier = 1;
-        while width_multiplier * 2 + width_multiplier <= width / TOTAL_WIDTH {
-            width_multiplier += 1;
-        }
-        //let width_multiplier = std::cmp::max(width / TOTAL_WIDTH / 3, 1);
-        let height_multiplier = height / TOTAL_HEIGHT;
-
-        let game_multiplier = std::cmp::min(width_multiplier, height_multiplier);
-        let info_multiplier = std::cmp::max(game_multiplier / 2, 1);
-
-        // Since terminal characters are a 2:1 height:width ratio,
-        //  2 characters will be used to create a square pixel
-        const COLUMN_MULTIPLIER: u16 = 2;
-
-        if column < GAME_WIDTH as u16 {
+        if column < game_width {
             // Game Section
             let x_start = column * game_multiplier * COLUMN_MULTIPLIER;
             let x_end = x_start + game_multiplier * COLUMN_MULTIPLIER;
@@ -417,7 +1077,7 @@ impl App {
             }
         } else {
             // Info Section
-            let adjusted_column = column - GAME_WIDTH + GAME_WIDTH * game_multiplier;
+            let adjusted_column = column - game_width + game_width * game_multiplier;
             let x_start = adjusted_column * info_multiplier * COLUMN_MULTIPLIER;
             let x_end = x_start + info_multiplier * COLUMN_MULTIPLIER;
             let y_start = row * info_multiplier;
@@ -437,25 +1097,148 @@ impl App {
         Ok(())
     }
 
+    /// Prints text onto the info section, starting at the given row and column.
+    ///
+    /// Text isn't scaled like the pixels are, so anything longer than the previous text printed at
+    /// the same spot should be padded to cover it up.
+    fn print(&mut self, row: u16, column: u16, text: &str) -> crossterm::Result<()> {
+        let (game_multiplier, info_multiplier) = self.multipliers();
+        let game_width = self.game_width();
+        let adjusted_column = column - game_width + game_width * game_multiplier;
+
+        self.stdout
+            .queue(cursor::MoveTo(
+                adjusted_column * info_multiplier * COLUMN_MULTIPLIER,
+                row * info_multiplier,
+            ))?
+            .queue(style::PrintStyledContent(
+                crossterm::style::style(text)
+                    .with(Color::White)
+                    .on(Color::Black),
+            ))?;
+        Ok(())
+    }
+
+    /// Returns the given line of the callout, or an empty line when there isn't one
+    fn callout_line(&self, line: usize) -> String {
+        match &self.callout {
+            Some((callout, _)) => callout.get(line).cloned().unwrap_or_default(),
+            None => String::new(),
+        }
+    }
+
+    /// Paints the game stats on the info pane, to the right of the next pieces
+    fn paint_stats(&mut self) -> crossterm::Result<()> {
+        let stats = [
+            format!("SCORE {}", self.score),
+            format!("LINES {}", self.lines),
+            format!("LEVEL {}", self.level),
+            self.timer_text(),
+            match self.mode {
+                Mode::Dig(total) => format!("GARBAGE {}", total - self.garbage_cleared),
+                _ => String::new(),
+            },
+            match self.scorer.combo() {
+                combo if combo > 0 => format!("COMBO {}", combo),
+                _ => String::new(),
+            },
+            self.callout_line(0),
+            self.callout_line(1),
+            String::new(),
+            format!("SEED {}", self.seed),
+        ];
+
+        for (i, stat) in stats.iter().enumerate() {
+            let line = format!("{:<width$}", stat, width = STATS_WIDTH);
+            self.print(EMPTY_TOP_INFO_ROWS + i as u16, self.stats_column(), &line)?;
+        }
+        self.stdout.flush()?;
+        Ok(())
+    }
+
+    /// Returns the timer line of the stats, which is empty for modes that aren't timed
+    fn timer_text(&self) -> String {
+        if self.mode.is_timed() {
+            format!("TIME {}", format_time(self.mode.timer(self.elapsed())))
+        } else {
+            String::new()
+        }
+    }
+
+    /// Repaints only the timer line of the stats, since it changes on every loop
+    fn paint_timer(&mut self) -> crossterm::Result<()> {
+        let line = format!("{:<width$}", self.timer_text(), width = STATS_WIDTH);
+        self.print(EMPTY_TOP_INFO_ROWS + TIMER_LINE, self.stats_column(), &line)?;
+        self.stdout.flush()?;
+        Ok(())
+    }
+
+    /// Returns the width of the game section, including the borders
+    fn game_width(&self) -> u16 {
+        (self.board.width * self.board.scale) as u16 + 2 * GAME_BORDER_WIDTH
+    }
+
+    /// Returns the height of the game section, including the borders
+    fn game_height(&self) -> u16 {
+        (self.board.height * self.board.scale) as u16 + 2 * GAME_BORDER_WIDTH
+    }
+
+    /// Returns the column that the info box starts at
+    fn info_column(&self) -> u16 {
+        self.game_width() + INFO_PADDING
+    }
+
+    /// Returns the column that the stats text starts at
+    fn stats_column(&self) -> u16 {
+        self.info_column() + INFO_WIDTH + INFO_PADDING
+    }
+
+    /// Returns the column pieces spawn at, so that they are centered on the board
+    fn spawn_column(&self) -> i16 {
+        (self.board.width as i16 - INFO_WIDTH as i16) / 2 + GAME_BORDER_WIDTH as i16
+    }
+
+    /// Returns how many terminal rows each pixel takes up for the game section and the info
+    /// section, based on the size of the terminal and the board
+    fn multipliers(&self) -> (u16, u16) {
+        let (width, height) =
+            crossterm::terminal::size().expect("Could not get terminal dimensions.");
+        let total_width = (self.stats_column() - INFO_PADDING) * COLUMN_MULTIPLIER;
+        let total_height = std::cmp::max(self.game_height(), INFO_TOTAL_HEIGHT);
+
+        // Keep a 2:1 ratio between the game section and the info section
+        let mut width_multiplier = 1;
+        while width_multiplier * 2 + width_multiplier <= width / total_width {
+            width_multiplier += 1;
+        }
+        let height_multiplier = height / total_height;
+
+        // Tall boards might not fit, in which case the bottom gets cut off
+        let game_multiplier = std::cmp::min(width_multiplier, height_multiplier).max(1);
+        let info_multiplier = std::cmp::max(game_multiplier / 2, 1);
+        (game_multiplier, info_multiplier)
+    }
+
     /// Paints the grey game border
     fn paint_game_border(&mut self) -> crossterm::Result<()> {
         // Paint left and right borders of game box
-        for r in 0..TOTAL_HEIGHT {
+        let (game_width, game_height) = (self.game_width(), self.game_height());
+        for r in 0..game_height {
             for c in 0..GAME_BORDER_WIDTH {
                 self.paint(r, c, Color::Grey)?;
             }
-            for c in (GAME_WIDTH - GAME_BORDER_WIDTH)..GAME_WIDTH {
+            for c in (game_width - GAME_BORDER_WIDTH)..game_width {
                 self.paint(r, c, Color::Grey)?;
             }
         }
 
         // Paint bottom border of game box
-        for c in 0..GAME_WIDTH {
-            self.paint(TOTAL_HEIGHT - GAME_BORDER_WIDTH, c, Color::Grey)?;
+        for c in 0..game_width {
+            self.paint(game_height - GAME_BORDER_WIDTH, c, Color::Grey)?;
         }
 
         // Paint top border of game box
-        for c in 0..12 {
+        for c in 0..game_width {
             self.paint(0, c, Color::Grey)?;
         }
 
@@ -464,12 +1247,13 @@ impl App {
 
     /// Paints all of the things necessary for the board game to the screen
     fn init(&mut self) -> crossterm::Result<()> {
-        crossterm::style::SetBackgroundColor(crossterm::style::Color::Black);
         crossterm::terminal::enable_raw_mode()?;
         self.stdout.queue(cursor::Hide)?;
         self.clear_screen()?;
         self.paint_game_border()?;
         self.paint_next_piece()?;
+        self.paint_hold_piece()?;
+        self.paint_stats()?;
         self.paint_board(self.board.height as u16)?;
         self.stdout.flush()?;
 
@@ -479,9 +1263,18 @@ impl App {
 
 /// Starts the game
 fn main() -> crossterm::Result<()> {
-    let mut app = App::new();
+    let config = match Config::from_args() {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
+    };
+
+    let mut app = App::new(config);
     app.init()?;
     app.run()?;
+    app.show_results()?;
 
     Ok(())
 }
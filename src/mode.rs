@@ -0,0 +1,112 @@
+use crate::board::GameOverReason;
+use crate::level::MASTER_MAX_LEVEL;
+use std::fmt;
+use std::time::Duration;
+
+/// The different ways that a game can be played
+#[derive(Clone, Copy)]
+pub enum Mode {
+    /// Play for as long as possible, with the levels speeding up as lines are cleared
+    Marathon,
+    /// Clear the given number of lines as fast as possible
+    Sprint(i32),
+    /// Score as many points as possible before the time runs out
+    Ultra(Duration),
+    /// Dig through the given number of lines of cheese garbage as fast as possible
+    Dig(i32),
+    /// Play for as long as you like, topping out only clears the board
+    Zen,
+    /// Survive 20G gravity all the way to the last level, while the delays get shorter
+    Master,
+}
+
+impl Mode {
+    /// Returns how the game ends when the goal of the mode has been reached, given the lines and
+    /// garbage lines cleared, the level and the time played so far
+    pub fn ending(
+        &self,
+        lines: i32,
+        garbage: i32,
+        level: i32,
+        elapsed: Duration,
+    ) -> Option<Ending> {
+        match self {
+            Mode::Master if level >= MASTER_MAX_LEVEL => Some(Ending::Complete),
+            Mode::Sprint(goal) if lines >= *goal => Some(Ending::Complete),
+            Mode::Ultra(time) if elapsed >= *time => Some(Ending::TimeUp),
+            Mode::Dig(goal) if garbage >= *goal => Some(Ending::Complete),
+            _ => None,
+        }
+    }
+
+    /// Returns the time shown on the timer, which counts down to the end of the game for modes
+    /// with a time limit
+    pub fn timer(&self, elapsed: Duration) -> Duration {
+        match self {
+            Mode::Ultra(time) => time.checked_sub(elapsed).unwrap_or_default(),
+            _ => elapsed,
+        }
+    }
+
+    /// Returns whether or not the level goes up as lines are cleared. Timed modes stay at the
+    /// starting level, so every run is played at the same speed.
+    pub fn has_levels(&self) -> bool {
+        matches!(self, Mode::Marathon)
+    }
+
+    /// Returns whether or not the time played is shown while playing
+    pub fn is_timed(&self) -> bool {
+        matches!(
+            self,
+            Mode::Sprint(_) | Mode::Ultra(_) | Mode::Dig(_) | Mode::Master
+        )
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mode::Marathon => write!(f, "MARATHON"),
+            Mode::Sprint(goal) => write!(f, "{} LINE SPRINT", goal),
+            Mode::Ultra(time) => write!(f, "{} SECOND ULTRA", time.as_secs()),
+            Mode::Dig(goal) => write!(f, "{} LINE DIG", goal),
+            Mode::Zen => write!(f, "ZEN"),
+            Mode::Master => write!(f, "20G MASTER"),
+        }
+    }
+}
+
+/// The ways that a game can come to an end
+#[derive(Clone, Copy)]
+pub enum Ending {
+    /// The player topped out
+    GameOver(GameOverReason),
+    /// The goal of the mode was reached
+    Complete,
+    /// The time limit of the mode ran out
+    TimeUp,
+    /// The player quit the game
+    Quit,
+}
+
+impl fmt::Display for Ending {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Ending::GameOver(reason) => write!(f, "GAME OVER! {}", reason),
+            Ending::Complete => write!(f, "COMPLETE!"),
+            Ending::TimeUp => write!(f, "TIME UP!"),
+            Ending::Quit => write!(f, "Aborted"),
+        }
+    }
+}
+
+/// Returns the time formatted the same way as the timer, e.g. 1:02.345
+pub fn format_time(time: Duration) -> String {
+    let millis = time.as_millis();
+    format!(
+        "{}:{:02}.{:03}",
+        millis / 60_000,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
@@ -1,8 +1,11 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
 use rand::Rng;
+use std::collections::VecDeque;
 
 /// Contains all of the possible pieces as well as their rotations
 /// All of them have unique rotations except for the O (Square Block) Piece
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum Piece {
     I,
     J,
@@ -34,35 +37,35 @@ pub enum Piece {
     Z2,
 }
 
-pub static O: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 1, 0], [0, 1, 1, 0], [0, 0, 0, 0]];
-
-pub static I0: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [1, 1, 1, 1], [0, 0, 0, 0], [0, 0, 0, 0]];
-pub static J0: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [1, 0, 0, 0], [1, 1, 1, 0], [0, 0, 0, 0]];
-pub static L0: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 0, 1, 0], [1, 1, 1, 0], [0, 0, 0, 0]];
-pub static S0: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 1, 0], [1, 1, 0, 0], [0, 0, 0, 0]];
-pub static T0: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 0, 0], [1, 1, 1, 0], [0, 0, 0, 0]];
-pub static Z0: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [1, 1, 0, 0], [0, 1, 1, 0], [0, 0, 0, 0]];
-
-pub static IR: &'static [[u8; 4]; 4] = &[[0, 0, 1, 0], [0, 0, 1, 0], [0, 0, 1, 0], [0, 0, 1, 0]];
-pub static JR: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 1, 0], [0, 1, 0, 0], [0, 1, 0, 0]];
-pub static LR: &'static [[u8; 4]; 4] = &[[0, 1, 0, 0], [0, 1, 0, 0], [0, 1, 1, 0], [0, 0, 0, 0]];
-pub static SR: &'static [[u8; 4]; 4] = &[[0, 1, 0, 0], [0, 1, 1, 0], [0, 0, 1, 0], [0, 0, 0, 0]];
-pub static TR: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 0, 0], [0, 1, 1, 0], [0, 1, 0, 0]];
-pub static ZR: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 0, 1, 0], [0, 1, 1, 0], [0, 1, 0, 0]];
-
-pub static I2: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 0, 0, 0], [1, 1, 1, 1], [0, 0, 0, 0]];
-pub static J2: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 0, 0, 0], [1, 1, 1, 0], [0, 0, 1, 0]];
-pub static L2: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 0, 0, 0], [1, 1, 1, 0], [1, 0, 0, 0]];
-pub static S2: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 0, 0, 0], [0, 1, 1, 0], [1, 1, 0, 0]];
-pub static T2: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 0, 0, 0], [1, 1, 1, 0], [0, 1, 0, 0]];
-pub static Z2: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 0, 0, 0], [1, 1, 0, 0], [0, 1, 1, 0]];
-
-pub static IL: &'static [[u8; 4]; 4] = &[[0, 1, 0, 0], [0, 1, 0, 0], [0, 1, 0, 0], [0, 1, 0, 0]];
-pub static JL: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 0, 0], [0, 1, 0, 0], [1, 1, 0, 0]];
-pub static LL: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [1, 1, 0, 0], [0, 1, 0, 0], [0, 1, 0, 0]];
-pub static SL: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [1, 0, 0, 0], [1, 1, 0, 0], [0, 1, 0, 0]];
-pub static TL: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 0, 0], [1, 1, 0, 0], [0, 1, 0, 0]];
-pub static ZL: &'static [[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 0, 0], [1, 1, 0, 0], [1, 0, 0, 0]];
+pub static O: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 1, 0], [0, 1, 1, 0], [0, 0, 0, 0]];
+
+pub static I0: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [1, 1, 1, 1], [0, 0, 0, 0], [0, 0, 0, 0]];
+pub static J0: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [1, 0, 0, 0], [1, 1, 1, 0], [0, 0, 0, 0]];
+pub static L0: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 0, 1, 0], [1, 1, 1, 0], [0, 0, 0, 0]];
+pub static S0: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 1, 0], [1, 1, 0, 0], [0, 0, 0, 0]];
+pub static T0: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 0, 0], [1, 1, 1, 0], [0, 0, 0, 0]];
+pub static Z0: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [1, 1, 0, 0], [0, 1, 1, 0], [0, 0, 0, 0]];
+
+pub static IR: &[[u8; 4]; 4] = &[[0, 0, 1, 0], [0, 0, 1, 0], [0, 0, 1, 0], [0, 0, 1, 0]];
+pub static JR: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 1, 0], [0, 1, 0, 0], [0, 1, 0, 0]];
+pub static LR: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 0, 0], [0, 1, 0, 0], [0, 1, 1, 0]];
+pub static SR: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 0, 0], [0, 1, 1, 0], [0, 0, 1, 0]];
+pub static TR: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 0, 0], [0, 1, 1, 0], [0, 1, 0, 0]];
+pub static ZR: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 0, 1, 0], [0, 1, 1, 0], [0, 1, 0, 0]];
+
+pub static I2: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 0, 0, 0], [1, 1, 1, 1], [0, 0, 0, 0]];
+pub static J2: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 0, 0, 0], [1, 1, 1, 0], [0, 0, 1, 0]];
+pub static L2: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 0, 0, 0], [1, 1, 1, 0], [1, 0, 0, 0]];
+pub static S2: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 0, 0, 0], [0, 1, 1, 0], [1, 1, 0, 0]];
+pub static T2: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 0, 0, 0], [1, 1, 1, 0], [0, 1, 0, 0]];
+pub static Z2: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 0, 0, 0], [1, 1, 0, 0], [0, 1, 1, 0]];
+
+pub static IL: &[[u8; 4]; 4] = &[[0, 1, 0, 0], [0, 1, 0, 0], [0, 1, 0, 0], [0, 1, 0, 0]];
+pub static JL: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 0, 0], [0, 1, 0, 0], [1, 1, 0, 0]];
+pub static LL: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [1, 1, 0, 0], [0, 1, 0, 0], [0, 1, 0, 0]];
+pub static SL: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [1, 0, 0, 0], [1, 1, 0, 0], [0, 1, 0, 0]];
+pub static TL: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 0, 0], [1, 1, 0, 0], [0, 1, 0, 0]];
+pub static ZL: &[[u8; 4]; 4] = &[[0, 0, 0, 0], [0, 1, 0, 0], [1, 1, 0, 0], [1, 0, 0, 0]];
 
 /// Returns the piece that the enum represents
 pub fn get_piece(piece: Piece) -> &'static [[u8; 4]; 4] {
@@ -138,9 +141,153 @@ pub fn rotate(piece: Piece) -> Piece {
     }
 }
 
+/// Returns the rotation of the piece in a counter clockwise fashion
+pub fn rotate_ccw(piece: Piece) -> Piece {
+    rotate(rotate(rotate(piece)))
+}
+
+/// Returns the piece flipped 180 degrees
+pub fn rotate_180(piece: Piece) -> Piece {
+    rotate(rotate(piece))
+}
+
+/// Returns the piece in its spawn orientation
+pub fn spawn_orientation(piece: Piece) -> Piece {
+    match piece {
+        Piece::I | Piece::IR | Piece::I2 | Piece::IL => Piece::I,
+        Piece::J | Piece::JR | Piece::J2 | Piece::JL => Piece::J,
+        Piece::L | Piece::LR | Piece::L2 | Piece::LL => Piece::L,
+        Piece::O => Piece::O,
+        Piece::S | Piece::SR | Piece::S2 | Piece::SL => Piece::S,
+        Piece::T | Piece::TR | Piece::T2 | Piece::TL => Piece::T,
+        Piece::Z | Piece::ZR | Piece::Z2 | Piece::ZL => Piece::Z,
+    }
+}
+
+/// The seven kinds of tetrominos, without their rotations. This is what the board remembers about
+/// the blocks of a piece once it has been fixed in place.
+#[derive(Clone, Copy, PartialEq)]
+pub enum PieceKind {
+    I,
+    J,
+    L,
+    O,
+    S,
+    T,
+    Z,
+}
+
+/// Returns the kind of the piece, whichever way it is rotated
+pub fn kind(piece: Piece) -> PieceKind {
+    match spawn_orientation(piece) {
+        Piece::I => PieceKind::I,
+        Piece::J => PieceKind::J,
+        Piece::L => PieceKind::L,
+        Piece::O => PieceKind::O,
+        Piece::S => PieceKind::S,
+        Piece::T => PieceKind::T,
+        _ => PieceKind::Z,
+    }
+}
+
+/// Returns the orientation of the piece as an index, where 0 is the spawn orientation, 1 is R, 2
+/// is the 180 orientation and 3 is L. This is what the SRS kick tables are indexed by.
+pub fn orientation(piece: Piece) -> usize {
+    match piece {
+        Piece::I | Piece::J | Piece::L | Piece::O | Piece::S | Piece::T | Piece::Z => 0,
+        Piece::IR | Piece::JR | Piece::LR | Piece::SR | Piece::TR | Piece::ZR => 1,
+        Piece::I2 | Piece::J2 | Piece::L2 | Piece::S2 | Piece::T2 | Piece::Z2 => 2,
+        Piece::IL | Piece::JL | Piece::LL | Piece::SL | Piece::TL | Piece::ZL => 3,
+    }
+}
+
+/// Two of the corners around the middle of a T piece, as (row, column) offsets in its 4x4 grid
+pub type Corners = [(i16, i16); 2];
+
+/// Returns the front and back corners of the 3x3 box around a T piece. The front corners are the
+/// two on the side the T is pointing towards.
+pub fn t_corners(piece: Piece) -> (Corners, Corners) {
+    let (top, bottom) = ([(1, 0), (1, 2)], [(3, 0), (3, 2)]);
+    let (left, right) = ([(1, 0), (3, 0)], [(1, 2), (3, 2)]);
+    match orientation(piece) {
+        0 => (top, bottom),
+        1 => (right, left),
+        2 => (bottom, top),
+        _ => (left, right),
+    }
+}
+
+/// SRS kick tests are written the same way as the guideline, (x, y) where positive y is upwards.
+/// Callers need to flip the y value since the board grows downwards.
+type Kicks = [(i16, i16); 5];
+
+static NO_KICKS: &[(i16, i16)] = &[(0, 0)];
+
+/// J, L, S, T, Z clockwise kicks indexed by the starting orientation: 0->R, R->2, 2->L, L->0
+static JLSTZ_CW_KICKS: [Kicks; 4] = [
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+];
+
+/// J, L, S, T, Z counter clockwise kicks indexed by the starting orientation: 0->L, R->0, 2->R,
+/// L->2
+static JLSTZ_CCW_KICKS: [Kicks; 4] = [
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+];
+
+/// I clockwise kicks indexed by the starting orientation: 0->R, R->2, 2->L, L->0
+static I_CW_KICKS: [Kicks; 4] = [
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+];
+
+/// I counter clockwise kicks indexed by the starting orientation: 0->L, R->0, 2->R, L->2
+static I_CCW_KICKS: [Kicks; 4] = [
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+];
+
+/// 180 kicks aren't part of SRS, so these are the commonly used SRS+ tests, indexed by the starting
+/// orientation: 0->2, R->L, 2->0, L->R
+static FLIP_KICKS: [[(i16, i16); 6]; 4] = [
+    [(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)],
+    [(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)],
+    [(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)],
+    [(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)],
+];
+
+/// Returns the SRS kick tests to try, in order, when rotating from `piece` into `rotated`
+///
+/// The first test that doesn't collide is the one that should be used. If all of them fail, the
+/// rotation fails and the piece stays where it is.
+pub fn kicks(piece: Piece, rotated: Piece) -> &'static [(i16, i16)] {
+    let from = orientation(piece);
+    let to = orientation(rotated);
+    let clockwise = to == (from + 1) % 4;
+    let counter_clockwise = to == (from + 3) % 4;
+
+    match piece {
+        Piece::O => NO_KICKS,
+        Piece::I | Piece::IR | Piece::I2 | Piece::IL if clockwise => &I_CW_KICKS[from],
+        Piece::I | Piece::IR | Piece::I2 | Piece::IL if counter_clockwise => &I_CCW_KICKS[from],
+        _ if clockwise => &JLSTZ_CW_KICKS[from],
+        _ if counter_clockwise => &JLSTZ_CCW_KICKS[from],
+        _ if from != to => &FLIP_KICKS[from],
+        _ => NO_KICKS,
+    }
+}
+
 /// Returns a random piece in the initial spawn orientation
-pub fn random_piece() -> Piece {
-    let mut rng = rand::thread_rng();
+pub fn random_piece(rng: &mut StdRng) -> Piece {
     match rng.gen_range(0..=6) {
         0 => Piece::I,
         1 => Piece::J,
@@ -152,3 +299,228 @@ pub fn random_piece() -> Piece {
         _ => panic!("RNG went out of range for random piece."),
     }
 }
+
+/// All of the pieces in their spawn orientation
+static PIECES: [Piece; 7] = [
+    Piece::I,
+    Piece::J,
+    Piece::L,
+    Piece::O,
+    Piece::S,
+    Piece::T,
+    Piece::Z,
+];
+
+/// Decides the order that the pieces are dealt out in
+pub trait Randomizer {
+    /// Returns the next piece in its spawn orientation. All of the randomness comes from the given
+    /// rng, so the same seed always deals the same pieces.
+    fn draw(&mut self, rng: &mut StdRng) -> Piece;
+}
+
+/// Returns the randomizer with the given name, or None if there isn't one
+pub fn randomizer(name: &str) -> Option<Box<dyn Randomizer>> {
+    match name {
+        "bag" | "7bag" => Some(Box::new(Bag::new(1))),
+        "14bag" => Some(Box::new(Bag::new(2))),
+        "nes" => Some(Box::new(Nes::new())),
+        "tgm" => Some(Box::new(Tgm::new(&[Piece::Z; 4], 4))),
+        "tgm2" => Some(Box::new(Tgm::new(
+            &[Piece::Z, Piece::S, Piece::S, Piece::Z],
+            6,
+        ))),
+        "random" => Some(Box::new(Random)),
+        _ => None,
+    }
+}
+
+/// A bag randomizer. The bag holds a number of copies of all seven pieces, which are shuffled and
+/// dealt out in that order before they get shuffled again, so there can never be a long drought or
+/// flood of any one piece. One copy is the standard 7-bag and two copies is the 14-bag.
+pub struct Bag {
+    copies: usize,
+    pieces: Vec<Piece>,
+}
+
+impl Bag {
+    /// Creates an empty bag, which gets filled on the first draw
+    pub fn new(copies: usize) -> Self {
+        Self {
+            copies,
+            pieces: Vec::with_capacity(PIECES.len() * copies),
+        }
+    }
+}
+
+impl Randomizer for Bag {
+    /// Returns the next piece out of the bag, refilling and shuffling it once it runs out
+    fn draw(&mut self, rng: &mut StdRng) -> Piece {
+        if self.pieces.is_empty() {
+            for _ in 0..self.copies {
+                self.pieces.extend_from_slice(&PIECES);
+            }
+            self.pieces.shuffle(rng);
+        }
+        self.pieces.pop().unwrap()
+    }
+}
+
+/// The NES randomizer. A piece is rolled out of eight options, and if the roll lands on the
+/// previous piece or on the eighth option, it is rerolled once out of the seven pieces.
+pub struct Nes {
+    previous: Option<Piece>,
+}
+
+impl Nes {
+    pub fn new() -> Self {
+        Self { previous: None }
+    }
+}
+
+impl Randomizer for Nes {
+    fn draw(&mut self, rng: &mut StdRng) -> Piece {
+        let piece = match PIECES.get(rng.gen_range(0..=PIECES.len())) {
+            Some(&piece) if Some(piece) != self.previous => piece,
+            _ => random_piece(rng),
+        };
+        self.previous = Some(piece);
+        piece
+    }
+}
+
+/// The TGM history randomizer. The last four pieces are remembered and a piece is rerolled up to
+/// the given number of times while it is in the history, so it is rolled at most one more time
+/// than that. The first piece is never an S, Z or O.
+pub struct Tgm {
+    history: VecDeque<Piece>,
+    rerolls: usize,
+    first: bool,
+}
+
+impl Tgm {
+    /// Creates the randomizer with the starting history, e.g. TGM starts with four Z pieces and
+    /// rerolls 4 times, while TGM2 starts with Z, S, S, Z and rerolls 6 times
+    pub fn new(history: &[Piece], rerolls: usize) -> Self {
+        Self {
+            history: history.iter().copied().collect(),
+            rerolls,
+            first: true,
+        }
+    }
+}
+
+impl Randomizer for Tgm {
+    fn draw(&mut self, rng: &mut StdRng) -> Piece {
+        let piece = if self.first {
+            self.first = false;
+            *[Piece::I, Piece::J, Piece::L, Piece::T]
+                .choose(rng)
+                .unwrap()
+        } else {
+            let mut piece = random_piece(rng);
+            for _ in 0..self.rerolls {
+                if !self.history.contains(&piece) {
+                    break;
+                }
+                piece = random_piece(rng);
+            }
+            piece
+        };
+
+        self.history.pop_front();
+        self.history.push_back(piece);
+        piece
+    }
+}
+
+/// Picks every piece uniformly at random, with no protection against droughts or floods
+pub struct Random;
+
+impl Randomizer for Random {
+    fn draw(&mut self, rng: &mut StdRng) -> Piece {
+        random_piece(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    /// Returns the filled cells of the piece as (row, column) in its 4x4 grid
+    fn cells(piece: Piece) -> Vec<(i16, i16)> {
+        let mut cells = vec![];
+        for (r, row) in get_piece(piece).iter().enumerate() {
+            for (c, &cell) in row.iter().enumerate() {
+                if cell == 1 {
+                    cells.push((r as i16, c as i16));
+                }
+            }
+        }
+        cells.sort_unstable();
+        cells
+    }
+
+    #[test]
+    fn jlstz_rotations_turn_around_the_srs_center() {
+        // The SRS center of J, L, S, T and Z is row 2, column 1 of the grid, so turning a
+        // piece clockwise has to turn every block around that cell
+        for &piece in &[Piece::J, Piece::L, Piece::S, Piece::T, Piece::Z] {
+            let mut piece = piece;
+            for _ in 0..4 {
+                let mut turned: Vec<(i16, i16)> = cells(piece)
+                    .into_iter()
+                    .map(|(r, c)| (2 + (c - 1), 1 - (r - 2)))
+                    .collect();
+                turned.sort_unstable();
+                assert_eq!(turned, cells(rotate(piece)));
+                piece = rotate(piece);
+            }
+        }
+    }
+
+    #[test]
+    fn jlstz_kicks() {
+        assert_eq!(
+            kicks(Piece::T, Piece::TR),
+            &[(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)]
+        );
+        assert_eq!(
+            kicks(Piece::TR, Piece::T),
+            &[(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)]
+        );
+        assert_eq!(kicks(Piece::L, Piece::LR), kicks(Piece::S, Piece::SR));
+    }
+
+    #[test]
+    fn i_kicks() {
+        assert_eq!(
+            kicks(Piece::I, Piece::IR),
+            &[(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)]
+        );
+        assert_eq!(
+            kicks(Piece::IR, Piece::I),
+            &[(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)]
+        );
+    }
+
+    #[test]
+    fn o_does_not_kick() {
+        assert_eq!(kicks(Piece::O, rotate(Piece::O)), &[(0, 0)]);
+    }
+
+    #[test]
+    fn t_kicks_off_the_left_wall() {
+        // A T pointing right against the left wall can't turn to point down in place, so the
+        // first kick that fits is the one that moves it right
+        let board = Board::new(10, 20, 1);
+        let (row, column) = (10, 0);
+        assert!(!board.detect_collision(Piece::TR, row, column));
+
+        let rotated = rotate(Piece::TR);
+        let kick = kicks(Piece::TR, rotated)
+            .iter()
+            .position(|&(x, y)| !board.detect_collision(rotated, row - y, column + x));
+        assert_eq!(kick, Some(1));
+    }
+}
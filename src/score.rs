@@ -0,0 +1,203 @@
+/// Whether the piece was spun into place, which is only ever a T piece
+#[derive(Clone, Copy, PartialEq)]
+pub enum Spin {
+    None,
+    Mini,
+    Full,
+}
+
+/// Keeps track of the scoring that carries over from one piece to the next, which is the combo
+/// counter and whether the last line clear was a difficult one for back to back bonuses
+pub struct Scorer {
+    combo: i32,
+    back_to_back: bool,
+    last_clear: String,
+}
+
+impl Scorer {
+    pub fn new() -> Self {
+        Self {
+            combo: -1,
+            back_to_back: false,
+            last_clear: String::new(),
+        }
+    }
+
+    /// Returns the points for a piece being fixed to the board and clearing the given number of
+    /// lines, following the guideline scoring
+    ///
+    /// Singles, doubles, triples and tetrises are worth 100, 300, 500 and 800 times the level.
+    /// T-spins are worth 400, 800, 1200 and 1600 for zero to three lines and T-spin minis are worth
+    /// 100, 200 and 400 for zero to two lines. A tetris or a T-spin that clears lines right after
+    /// another one is worth 1.5 times as much, and every line clear in a row after the first adds
+    /// 50 times the combo count and the level.
+    ///
+    /// Clearing the whole board is a perfect clear, which is worth another 800, 1200, 1800 and
+    /// 2000 times the level for a single, double, triple and tetris, or 3200 times the level for a
+    /// back to back tetris.
+    pub fn line_clear(&mut self, lines: i32, spin: Spin, perfect_clear: bool, level: i32) -> i32 {
+        self.last_clear = clear_name(lines, spin);
+
+        let mut points = match (spin, lines) {
+            (Spin::None, 0) => 0,
+            (Spin::None, 1) => 100,
+            (Spin::None, 2) => 300,
+            (Spin::None, 3) => 500,
+            (Spin::None, _) => 800,
+            (Spin::Mini, 0) => 100,
+            (Spin::Mini, 1) => 200,
+            (Spin::Mini, _) => 400,
+            (Spin::Full, 0) => 400,
+            (Spin::Full, 1) => 800,
+            (Spin::Full, 2) => 1200,
+            (Spin::Full, _) => 1600,
+        } * level;
+
+        if lines == 0 {
+            // Zero line T-spins don't break back to back, but every piece without a line clear
+            // breaks the combo
+            self.combo = -1;
+            return points;
+        }
+
+        let difficult = lines >= 4 || spin != Spin::None;
+        let back_to_back = difficult && self.back_to_back;
+        if back_to_back {
+            points = points * 3 / 2;
+            self.last_clear = format!("B2B {}", self.last_clear);
+        }
+        self.back_to_back = difficult;
+
+        if perfect_clear {
+            points += match lines {
+                1 => 800,
+                2 => 1200,
+                3 => 1800,
+                _ if back_to_back => 3200,
+                _ => 2000,
+            } * level;
+        }
+
+        self.combo += 1;
+        points + 50 * self.combo * level
+    }
+
+    /// Returns the name of the last line clear or T-spin, e.g. "B2B T-SPIN DOUBLE". This is empty
+    /// when the last piece didn't do either.
+    pub fn last_clear(&self) -> &str {
+        &self.last_clear
+    }
+
+    /// Returns the current combo count, which is -1 when there isn't a combo going
+    pub fn combo(&self) -> i32 {
+        self.combo
+    }
+}
+
+/// Returns the points for soft dropping the piece the given number of cells
+pub fn soft_drop(cells: i32) -> i32 {
+    cells
+}
+
+/// Returns the points for hard dropping the piece the given number of cells
+pub fn hard_drop(cells: i32) -> i32 {
+    2 * cells
+}
+
+/// Returns the name of the line clear, or an empty string for a piece that didn't clear any lines
+fn clear_name(lines: i32, spin: Spin) -> String {
+    let lines = match lines {
+        0 => "",
+        1 => "SINGLE",
+        2 => "DOUBLE",
+        3 => "TRIPLE",
+        _ => "TETRIS",
+    };
+    match spin {
+        Spin::None => lines.to_string(),
+        Spin::Mini => format!("T-SPIN MINI {}", lines).trim_end().to_string(),
+        Spin::Full => format!("T-SPIN {}", lines).trim_end().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the points for a single line clear, without anything carried over from before
+    fn points(lines: i32, spin: Spin, level: i32) -> i32 {
+        Scorer::new().line_clear(lines, spin, false, level)
+    }
+
+    #[test]
+    fn line_clears() {
+        assert_eq!(points(0, Spin::None, 1), 0);
+        assert_eq!(points(1, Spin::None, 1), 100);
+        assert_eq!(points(2, Spin::None, 1), 300);
+        assert_eq!(points(3, Spin::None, 1), 500);
+        assert_eq!(points(4, Spin::None, 1), 800);
+        assert_eq!(points(4, Spin::None, 3), 2400);
+    }
+
+    #[test]
+    fn t_spins() {
+        assert_eq!(points(0, Spin::Full, 1), 400);
+        assert_eq!(points(1, Spin::Full, 1), 800);
+        assert_eq!(points(2, Spin::Full, 1), 1200);
+        assert_eq!(points(3, Spin::Full, 1), 1600);
+        assert_eq!(points(0, Spin::Mini, 1), 100);
+        assert_eq!(points(1, Spin::Mini, 1), 200);
+        assert_eq!(points(2, Spin::Mini, 2), 800);
+    }
+
+    #[test]
+    fn back_to_back() {
+        let mut scorer = Scorer::new();
+        assert_eq!(scorer.line_clear(4, Spin::None, false, 1), 800);
+        assert_eq!(scorer.line_clear(0, Spin::None, false, 1), 0);
+        assert_eq!(scorer.line_clear(2, Spin::Full, false, 1), 1800);
+        assert_eq!(scorer.last_clear(), "B2B T-SPIN DOUBLE");
+
+        // A single breaks back to back, so the next tetris is worth the normal amount
+        assert_eq!(scorer.line_clear(0, Spin::None, false, 1), 0);
+        assert_eq!(scorer.line_clear(1, Spin::None, false, 1), 100);
+        assert_eq!(scorer.line_clear(0, Spin::None, false, 1), 0);
+        assert_eq!(scorer.line_clear(4, Spin::None, false, 1), 800);
+        assert_eq!(scorer.last_clear(), "TETRIS");
+    }
+
+    #[test]
+    fn combos() {
+        let mut scorer = Scorer::new();
+        assert_eq!(scorer.combo(), -1);
+        assert_eq!(scorer.line_clear(1, Spin::None, false, 2), 200);
+        assert_eq!(scorer.combo(), 0);
+        assert_eq!(scorer.line_clear(1, Spin::None, false, 2), 300);
+        assert_eq!(scorer.line_clear(2, Spin::None, false, 2), 800);
+        assert_eq!(scorer.combo(), 2);
+
+        // A piece that doesn't clear any lines ends the combo
+        assert_eq!(scorer.line_clear(0, Spin::None, false, 2), 0);
+        assert_eq!(scorer.combo(), -1);
+        assert_eq!(scorer.line_clear(1, Spin::None, false, 2), 200);
+    }
+
+    #[test]
+    fn perfect_clears() {
+        assert_eq!(Scorer::new().line_clear(1, Spin::None, true, 1), 900);
+        assert_eq!(Scorer::new().line_clear(2, Spin::None, true, 1), 1500);
+        assert_eq!(Scorer::new().line_clear(3, Spin::None, true, 1), 2300);
+        assert_eq!(Scorer::new().line_clear(4, Spin::None, true, 1), 2800);
+
+        let mut scorer = Scorer::new();
+        scorer.line_clear(4, Spin::None, false, 1);
+        scorer.line_clear(0, Spin::None, false, 1);
+        assert_eq!(scorer.line_clear(4, Spin::None, true, 1), 1200 + 3200);
+    }
+
+    #[test]
+    fn drops() {
+        assert_eq!(soft_drop(5), 5);
+        assert_eq!(hard_drop(5), 10);
+    }
+}